@@ -5,9 +5,15 @@ use bindgen::{
 use convert_case::{Case, Casing};
 use std::path::PathBuf;
 
+/// Include directory used if SANE could not be located otherwise.
+const DEFAULT_INCLUDE_DIR: &str = "/usr/include";
+
 fn main() {
+    let include_dir = locate_sane();
+
     let bindings = bindgen::builder()
-        .header("/usr/include/sane/sane.h")
+        .header(include_dir.join("sane/sane.h").to_string_lossy())
+        .clang_arg(format!("-I{}", include_dir.display()))
         .default_enum_style(EnumVariation::NewType {
             is_bitfield: false,
             is_global: false,
@@ -31,8 +37,39 @@ fn main() {
                 .collect::<PathBuf>(),
         )
         .unwrap();
+}
 
+/// Finds the directory containing `sane/sane.h` and emits the linker flags.
+///
+/// The `SANE_INCLUDE_DIR` and `SANE_LIB_DIR` environment variables take precedence.
+/// Otherwise, pkg-config is queried, and if that fails, the system default is used.
+fn locate_sane() -> PathBuf {
+    println!("cargo:rerun-if-env-changed=SANE_INCLUDE_DIR");
+    println!("cargo:rerun-if-env-changed=SANE_LIB_DIR");
+
+    let include_dir = std::env::var_os("SANE_INCLUDE_DIR").map(PathBuf::from);
+    let lib_dir = std::env::var_os("SANE_LIB_DIR").map(PathBuf::from);
+
+    if include_dir.is_none() && lib_dir.is_none() {
+        // pkg-config emits the linker flags itself.
+        if let Ok(library) = pkg_config::Config::new().probe("sane-backends") {
+            if let Some(dir) = library
+                .include_paths
+                .into_iter()
+                .find(|dir| dir.join("sane/sane.h").is_file())
+            {
+                return dir;
+            }
+            return PathBuf::from(DEFAULT_INCLUDE_DIR);
+        }
+    }
+
+    if let Some(lib_dir) = lib_dir {
+        println!("cargo:rustc-link-search=native={}", lib_dir.display());
+    }
     println!("cargo:rustc-link-lib=sane");
+
+    include_dir.unwrap_or_else(|| PathBuf::from(DEFAULT_INCLUDE_DIR))
 }
 
 #[derive(Debug)]
@@ -0,0 +1,77 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use libsane::scan::{FrameDecoder, FrameFormat, FrameParameters};
+
+/// Width of an A4 page at 300 DPI in pixels.
+const WIDTH: u32 = 2480;
+/// Height of an A4 page at 300 DPI in pixels.
+const HEIGHT: u32 = 3508;
+
+/// Creates the parameters and synthetic data of a frame with the given format.
+fn frame(format: FrameFormat, last_frame: bool, depth: u32) -> (FrameParameters, Vec<u8>) {
+    let samples = if format == FrameFormat::Rgb { 3 } else { 1 };
+    let bytes_per_line = (WIDTH * samples * depth).div_ceil(8);
+    let params = FrameParameters::new(
+        format,
+        last_frame,
+        bytes_per_line,
+        WIDTH,
+        Some(HEIGHT),
+        depth,
+    );
+    let data = (0..bytes_per_line * HEIGHT).map(|i| i as u8).collect();
+    (params, data)
+}
+
+fn decode(decoder: FrameDecoder, frames: &[(FrameParameters, Vec<u8>)]) -> FrameDecoder {
+    let mut decoder = decoder;
+    for (params, data) in frames {
+        decoder.write(black_box(data), params).unwrap();
+    }
+    decoder
+}
+
+fn bench_frame_decoder(c: &mut Criterion) {
+    let mut group = c.benchmark_group("frame_decoder");
+
+    let cases = [
+        (
+            "lineart_packed",
+            vec![frame(FrameFormat::Gray, true, 1)],
+            false,
+        ),
+        (
+            "lineart_bytes",
+            vec![frame(FrameFormat::Gray, true, 1)],
+            true,
+        ),
+        ("gray8", vec![frame(FrameFormat::Gray, true, 8)], false),
+        ("rgb8", vec![frame(FrameFormat::Rgb, true, 8)], false),
+        (
+            "rgb8_three_pass",
+            vec![
+                frame(FrameFormat::Red, false, 8),
+                frame(FrameFormat::Green, false, 8),
+                frame(FrameFormat::Blue, true, 8),
+            ],
+            false,
+        ),
+    ];
+
+    for (name, frames, as_bytes) in &cases {
+        let len = frames.iter().map(|(_, data)| data.len() as u64).sum();
+        group.throughput(Throughput::Bytes(len));
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                let decoder = FrameDecoder::builder()
+                    .decode_black_and_white_as_bytes(*as_bytes)
+                    .build();
+                decode(decoder, frames)
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_frame_decoder);
+criterion_main!(benches);
@@ -4,7 +4,8 @@ fn main() -> Result<(), libsane::Error> {
     println!("Version: {version}");
     println!("Lib Version: {}", libsane::LIB_VERSION);
 
-    let devices = sane.get_devices_as_boxed_slice(true)?;
+    let mut devices = sane.get_devices_as_boxed_slice(true)?;
+    devices.sort();
 
     println!("{devices:#?}");
 
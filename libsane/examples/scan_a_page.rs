@@ -1,13 +1,19 @@
-use std::io::{BufRead, BufReader, Write};
+use std::{
+    error::Error,
+    io::{BufRead, BufReader, Write},
+};
 
-use libsane::scan::{DecodedImage, DecodedImageFormat, FrameDecoder};
+use libsane::{
+    image::pnm,
+    scan::{DecodedImage, FrameDecoder},
+};
 
 const OUTPUT_FILE: &str = "./page.pam";
 
 /// This example prompts for a scanner device to be used and attempts to scan
 /// one page. The file will be saved at `./page.pam` in [Netpbm PAM] file format.
 /// PAM is a very simple uncompressed format that doesn't need an external library
-/// to be encoded, keeping this code simple.
+/// to be encoded, keeping this code simple. This example requires the `pnm` feature.
 ///
 /// To convert this file, you could use FFMPEG as follows:
 /// ```sh
@@ -15,7 +21,7 @@ const OUTPUT_FILE: &str = "./page.pam";
 /// ```
 ///
 /// [Netpbm PAM]: https://netpbm.sourceforge.net/doc/pam.html#visual
-fn main() -> Result<(), libsane::Error> {
+fn main() -> Result<(), Box<dyn Error>> {
     let (sane, version) = libsane::Sane::init_no_auth()?;
 
     println!("Version: {version}");
@@ -27,27 +33,17 @@ fn main() -> Result<(), libsane::Error> {
     println!("Scanning with device {}", device_info.name());
 
     let device = sane.connect(device_info.name())?;
-    let mut reader = device.scan_blocking();
-    let mut buf = Vec::new();
-
-    let mut decoder = FrameDecoder::builder()
-        .decode_black_and_white_as_bytes(true)
-        .build();
-
-    while let Some(mut frame_reader) = reader.next_frame()? {
-        buf.clear();
-        frame_reader.read_full_frame(&mut buf)?;
-        println!("Read frame: {:#?}", frame_reader.parameters());
-        println!("Frame has {} bytes", buf.len());
-
-        decoder
-            .write(&buf, frame_reader.parameters())
-            .expect("invalid data");
-    }
-
-    let image = decoder
-        .into_image()
-        .expect("not all necessary frames were received");
+    let decoder = FrameDecoder::builder().decode_black_and_white_as_bytes(true);
+    // Only the first image is scanned, even if the device has a document feeder.
+    let Some(image) = device.scan_blocking().images_with(decoder).next() else {
+        println!("No document to scan.");
+        return Ok(());
+    };
+    let image = image?;
+    println!(
+        "Scanned image: {}x{} {:?}",
+        image.width, image.height, image.format
+    );
 
     if let Err(err) = write_pam_image(&image, OUTPUT_FILE) {
         println!("Failed to write image to {OUTPUT_FILE}: {err}");
@@ -57,35 +53,15 @@ fn main() -> Result<(), libsane::Error> {
 }
 
 fn write_pam_image(image: &DecodedImage, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
-    let mut out_file = std::fs::OpenOptions::new()
+    let out_file = std::fs::OpenOptions::new()
         .write(true)
         .create(true)
         .truncate(true)
         .open(path)?;
+    let mut out_file = std::io::BufWriter::new(out_file);
 
-    let (depth, pam_tupletype, maxval) = match image.format {
-        DecodedImageFormat::BlackAndWhite => (1, "BLACKANDWHITE", 1u32),
-        DecodedImageFormat::Gray { bytes_per_pixel } => {
-            (1, "GRAYSCALE", (1 << (bytes_per_pixel * 8)) - 1)
-        }
-        DecodedImageFormat::Rgb { bytes_per_channel } => {
-            (3, "RGB", (1 << (bytes_per_channel * 8)) - 1)
-        }
-    };
-    write!(
-        out_file,
-        "P7\n\
-				WIDTH {width}\n\
-				HEIGHT {height}\n\
-				DEPTH {depth}\n\
-				MAXVAL {maxval}\n\
-				TUPLETYPE {pam_tupletype}\n\
-				ENDHDR\n",
-        width = image.width,
-        height = image.height,
-    )?;
-
-    out_file.write_all(&image.data)
+    pnm::write_pam(image, &mut out_file)?;
+    out_file.flush()
 }
 
 fn ask_for_device(devices: &[libsane::DeviceDescription]) -> &libsane::DeviceDescription {
@@ -0,0 +1,96 @@
+use std::error::Error;
+
+use libsane::{scan::ScanReader, sys, Sane, WithSane};
+use tokio::{fs::File, io::AsyncWriteExt, signal, sync::mpsc, task};
+
+const OUTPUT_FILE: &str = "./page.raw";
+
+/// This example scans one page with the first available device from within a
+/// Tokio runtime and streams the raw frame data to `./page.raw`. The scan can be
+/// cancelled by pressing Ctrl-C. This example requires the `tokio` feature.
+///
+/// SANE calls are blocking, so the device is driven by a blocking task, which
+/// sends the data to an asynchronous task writing the file.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let device = task::spawn_blocking(|| {
+        let (sane, version) = Sane::init_no_auth()?;
+        println!("Version: {version}");
+        Sane::connect_with(sane, libsane::SaneStr::EMPTY)
+    })
+    .await??;
+
+    let cancel = device.cancel_handle();
+    let (chunks_tx, chunks_rx) = mpsc::channel(16);
+    let writer = tokio::spawn(write_chunks(chunks_rx));
+
+    let mut scan = task::spawn_blocking(move || {
+        let mut reader = device.scan_blocking();
+        let res = read_frames(&mut reader, chunks_tx);
+        // The device is returned instead of being dropped here, so that it is
+        // guaranteed to outlive the cancel handle.
+        (reader, res)
+    });
+
+    let (reader, res) = loop {
+        tokio::select! {
+            res = &mut scan => break res?,
+            _ = signal::ctrl_c() => {
+                println!("Cancelling scan...");
+                // SAFETY: The device is owned by the scan task and only dropped after
+                // the task has returned it to us, which ends this loop.
+                unsafe { cancel.cancel() };
+            }
+        }
+    };
+    drop(reader);
+
+    let written = writer.await??;
+    match res {
+        Err(err) if err.sys_status() == sys::Status::Cancelled => {
+            println!("Scan cancelled, wrote {written} bytes to {OUTPUT_FILE}");
+        }
+        res => {
+            res?;
+            println!("Wrote {written} bytes to {OUTPUT_FILE}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads all frames of the image and sends the data in chunks.
+fn read_frames(
+    reader: &mut ScanReader<impl WithSane>,
+    chunks: mpsc::Sender<Vec<u8>>,
+) -> Result<(), libsane::Error> {
+    let mut buf = vec![0; 64 * 1024];
+    while let Some(mut frame_reader) = reader.next_frame()? {
+        println!("Reading frame: {:#?}", frame_reader.parameters());
+        loop {
+            match frame_reader.read_frame(&mut buf) {
+                Ok(len) => {
+                    if chunks.blocking_send(buf[..len].to_vec()).is_err() {
+                        // The writer failed, its error is reported instead.
+                        return Ok(());
+                    }
+                }
+                Err(err) if err.sys_status() == sys::Status::Eof => break,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes all received chunks into the output file.
+async fn write_chunks(mut chunks: mpsc::Receiver<Vec<u8>>) -> std::io::Result<u64> {
+    let mut file = File::create(OUTPUT_FILE).await?;
+    let mut written = 0;
+    while let Some(chunk) = chunks.recv().await {
+        file.write_all(&chunk).await?;
+        written += chunk.len() as u64;
+    }
+    file.flush().await?;
+    Ok(written)
+}
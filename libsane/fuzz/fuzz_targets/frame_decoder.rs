@@ -0,0 +1,89 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use libsane::{
+    scan::{FrameDecoder, FrameFormat, FrameParameters},
+    sys,
+};
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    black_and_white_as_bytes: bool,
+    invert_lineart: bool,
+    multi_band_channels: u8,
+    gamma: bool,
+    normalize_to_8bit: bool,
+    expected_lines: Option<u32>,
+    reserve: bool,
+    frames: Vec<Frame>,
+}
+
+#[derive(Debug, Arbitrary)]
+struct Frame {
+    format: u8,
+    last_frame: bool,
+    bytes_per_line: u32,
+    pixels_per_line: u32,
+    lines: Option<u32>,
+    depth: u32,
+    data: Vec<u8>,
+}
+
+impl Frame {
+    fn parameters(&self) -> FrameParameters {
+        let format = match self.format % 6 {
+            0 => FrameFormat::Gray,
+            1 => FrameFormat::Rgb,
+            2 => FrameFormat::Red,
+            3 => FrameFormat::Green,
+            4 => FrameFormat::Blue,
+            // a format unknown to SANE, which can only be reported by a backend
+            _ => {
+                return FrameParameters::from(sys::Parameters {
+                    format: sys::Frame(self.format.into()),
+                    last_frame: self.last_frame.into(),
+                    bytes_per_line: self.bytes_per_line as sys::Int,
+                    pixels_per_line: self.pixels_per_line as sys::Int,
+                    lines: self.lines.map_or(-1, |lines| lines as sys::Int),
+                    depth: self.depth as sys::Int,
+                })
+            }
+        };
+        FrameParameters::new(
+            format,
+            self.last_frame,
+            self.bytes_per_line,
+            self.pixels_per_line,
+            self.lines,
+            self.depth,
+        )
+    }
+}
+
+// The decoder must never panic on parameters and data provided by a backend, it
+// may only reject them with an error.
+fuzz_target!(|input: Input| {
+    let mut builder = FrameDecoder::builder()
+        .decode_black_and_white_as_bytes(input.black_and_white_as_bytes)
+        .invert_lineart(input.invert_lineart)
+        .multi_band_channels(input.multi_band_channels)
+        .gamma(if input.gamma { 2.2 } else { 1.0 })
+        .normalize_to_8bit(input.normalize_to_8bit);
+    if let Some(lines) = input.expected_lines {
+        builder = builder.expected_lines(lines);
+    }
+    let mut decoder = builder.build();
+    for frame in &input.frames {
+        let params = frame.parameters();
+        if input.reserve {
+            decoder.reserve_for(&params);
+        }
+        let _ = decoder.write(&frame.data, &params);
+    }
+    if let Ok(image) = decoder.into_image() {
+        // Reading every byte makes uninitialized memory visible to sanitizers.
+        let sum = image.data.iter().fold(0u8, |acc, b| acc.wrapping_add(*b));
+        std::hint::black_box(sum);
+    }
+});
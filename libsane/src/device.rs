@@ -3,18 +3,33 @@ pub mod options;
 pub mod scan;
 
 use core::ffi::c_void;
+use core::fmt;
 use core::ptr::NonNull;
-use std::mem::ManuallyDrop;
+use std::{
+    borrow::Borrow,
+    mem::ManuallyDrop,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 use bitflags::bitflags;
 
-use crate::{sys, Error, Sane, SaneStr, WithSane};
+use crate::{sys, Error, Sane, SaneStr, SaneString, WithSane};
 
-pub use enumerate::DeviceDescription;
+pub use enumerate::{DeviceDescription, DeviceList};
 
 pub(crate) struct RawDeviceHandle<S: WithSane> {
     handle: NonNull<c_void>,
     sane: S,
+    name: SaneString,
+    /// Whether an option change reported [`ControlInfo::RELOAD_PARAMS`] since the
+    /// parameters were last read.
+    params_dirty: AtomicBool,
+    /// Whether `handle` was closed because reconnecting failed. It must not be passed
+    /// to the library anymore.
+    closed: bool,
 }
 
 impl<S: WithSane> RawDeviceHandle<S> {
@@ -23,14 +38,52 @@ impl<S: WithSane> RawDeviceHandle<S> {
         // Prevents the device from being closed.
         let mut this = ManuallyDrop::new(self);
 
+        // SAFETY: This copies the value, but the original is ManuallyDrop and never accessed again.
+        let name = unsafe { (&mut this.name as *mut SaneString).read() };
+        let params_dirty = AtomicBool::new(this.params_dirty.load(Ordering::Relaxed));
+
         RawDeviceHandle {
             handle,
             // SAFETY: This copies the value, but the original is ManuallyDrop and never accessed again.
             sane: map_fn(unsafe { (&mut this.sane as *mut S).read() }),
+            name,
+            params_dirty,
+            closed: this.closed,
+        }
+    }
+
+    /// Closes the device and returns the inner `sane` value.
+    pub fn close(self) -> S {
+        let mut this = ManuallyDrop::new(self);
+        if !this.closed {
+            this.sane
+                // SAFETY: The handle is never accessed again, as `this` is never dropped.
+                .with_sane(|sane| unsafe { sane.sys_close(this.handle) });
+        }
+        // SAFETY: The name is dropped in place, but the original is ManuallyDrop and never accessed again.
+        unsafe { std::ptr::drop_in_place(&mut this.name) };
+        // SAFETY: This copies the value, but the original is ManuallyDrop and never accessed again.
+        unsafe { (&mut this.sane as *mut S).read() }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Returns the handle, or fails with [`Inval`][`crate::error::Status::Inval`] if it
+    /// was closed because reconnecting failed.
+    pub(crate) fn open_handle(&self) -> Result<NonNull<c_void>, Error> {
+        if self.closed {
+            Err(Error::from_sys_status(sys::Status::Inval))
+        } else {
+            Ok(self.handle)
         }
     }
 
     pub(crate) fn get_option(&self, index: u32) -> Option<options::DeviceOption<S>> {
+        if self.closed {
+            return None;
+        }
         let descriptor =
             // SAFETY: call is synchronized and device is not closed.
             self.with_sane(|sane| unsafe { sane.sys_get_option_descriptor(self.handle, index) });
@@ -43,12 +96,29 @@ impl<S: WithSane> RawDeviceHandle<S> {
     }
 
     pub fn get_parameters(&self) -> Result<scan::FrameParameters, Error> {
+        let handle = self.open_handle()?;
         // SAFETY: call is synchronized and device is not closed.
-        self.with_sane(|sane| unsafe { sane.sys_get_parameters(self.handle) })
-            .map(scan::FrameParameters::from)
+        let params = self.with_sane(|sane| unsafe { sane.sys_get_parameters(handle) })?;
+        self.parameters_read();
+        Ok(params.into())
+    }
+
+    /// Records that the parameters have just been read from the device.
+    pub(crate) fn parameters_read(&self) {
+        self.params_dirty.store(false, Ordering::Relaxed);
+    }
+
+    /// Records the [`ControlInfo`] returned by setting an option.
+    pub(crate) fn option_changed(&self, info: ControlInfo) {
+        if info.needs_reload_params() {
+            self.params_dirty.store(true, Ordering::Relaxed);
+        }
     }
 
     pub fn cancel(&self) {
+        if self.closed {
+            return;
+        }
         // SAFETY: The handle is valid, no synchronization needed by specification.
         unsafe { Sane::<()>::sys_cancel(self.handle) }
     }
@@ -60,8 +130,19 @@ unsafe impl<S: WithSane> Send for RawDeviceHandle<S> where S: Send {}
 // SAFETY: Every access to the handle is done though `S` and thus guarded.
 unsafe impl<S: WithSane> Sync for RawDeviceHandle<S> where S: Sync {}
 
+// Handles returned by `Sane::connect_shared` must be movable to another thread.
+const _: () = {
+    const fn assert_send<T: Send>() {}
+    assert_send::<DeviceHandle<Arc<std::sync::Mutex<Sane<()>>>>>();
+    #[cfg(feature = "parking_lot")]
+    assert_send::<DeviceHandle<Arc<parking_lot::Mutex<Sane<()>>>>>();
+};
+
 impl<S: WithSane> Drop for RawDeviceHandle<S> {
     fn drop(&mut self) {
+        if self.closed {
+            return;
+        }
         self.sane
             // SAFETY: This handle is dropped, which means that nothing else is referencing any resource to this handle.
             .with_sane(|sane| unsafe { sane.sys_close(self.handle) });
@@ -77,6 +158,8 @@ impl<S: WithSane> WithSane for RawDeviceHandle<S> {
 }
 
 bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[repr(transparent)]
     pub struct ControlInfo: u32 {
         const INEXACT = sys::INFO_INEXACT;
         const RELOAD_OPTIONS = sys::INFO_RELOAD_OPTIONS;
@@ -84,19 +167,165 @@ bitflags! {
     }
 }
 
+impl ControlInfo {
+    /// The value has been rounded or otherwise adjusted by the backend,
+    /// it should be read again.
+    pub const fn is_inexact(&self) -> bool {
+        self.contains(Self::INEXACT)
+    }
+
+    /// Other options have changed, their descriptors need to be read again.
+    pub const fn needs_reload_options(&self) -> bool {
+        self.contains(Self::RELOAD_OPTIONS)
+    }
+
+    /// The scan parameters have changed and need to be read again.
+    pub const fn needs_reload_params(&self) -> bool {
+        self.contains(Self::RELOAD_PARAMS)
+    }
+}
+
+impl fmt::Display for ControlInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags = [
+            (Self::INEXACT, "inexact"),
+            (Self::RELOAD_OPTIONS, "reload options"),
+            (Self::RELOAD_PARAMS, "reload parameters"),
+        ];
+        let mut first = true;
+        for (flag, name) in flags {
+            if self.contains(flag) {
+                if !first {
+                    f.write_str(", ")?;
+                }
+                f.write_str(name)?;
+                first = false;
+            }
+        }
+        if first {
+            f.write_str("none")?;
+        }
+        Ok(())
+    }
+}
+
 pub struct DeviceHandle<S: WithSane> {
     inner: RawDeviceHandle<S>,
+    /// Whether a frame is being acquired using the low-level scan API.
+    scanning: bool,
+    description: Option<DeviceDescription>,
 }
 
 impl<S: WithSane> DeviceHandle<S> {
     pub fn map_sane<N: WithSane>(self, map_fn: impl FnOnce(S) -> N) -> DeviceHandle<N> {
         DeviceHandle {
             inner: self.inner.map_sane(map_fn),
+            scanning: self.scanning,
+            description: self.description,
         }
     }
+
+    /// Closes the connection to the device and returns the inner [`WithSane`] value, for
+    /// example to regain exclusive ownership of an [`Arc`] passed to
+    /// [`Sane::connect_shared`]. A pending scan is cancelled. The handle cannot be used
+    /// anymore afterwards.
+    pub fn close(self) -> S {
+        self.inner.close()
+    }
+
+    /// Grants access to the raw handle of the device, which allows calling functions of
+    /// [`sys`][`crate::sys`] that are not covered by this crate. The callback is run while
+    /// holding the [`Sane`] instance, so calls made within are synchronized.
+    ///
+    /// # Safety
+    /// The handle must not have been closed by a failed [reconnect][`Self::reconnect`].
+    /// The callback must not close the handle or leave the device in a state this handle
+    /// does not expect, such as starting a scan or changing the I/O mode outside of a
+    /// [`ScanReader`][`scan::ScanReader`]. The handle must not be used after the callback
+    /// returns. Other methods of this crate must not be called from within the callback,
+    /// as the [`WithSane`] value may not be re-entrant, for example if it is a mutex.
+    pub unsafe fn with_raw_handle<R>(
+        &self,
+        callback: impl FnOnce(NonNull<c_void>, &Sane<S::Auth>) -> R,
+    ) -> R {
+        let handle = self.inner.handle;
+        self.with_sane(|sane| callback(handle, sane))
+    }
+
+    /// Closes the connection to the device and opens it again using the stored
+    /// [name][`Self::name`]. This can be used to recover from an
+    /// [`IoError`][`crate::error::Status::IoError`], for example when a network
+    /// scanner dropped the connection.
+    ///
+    /// The old connection is closed before the new one is opened, as most backends
+    /// report the device as [busy][`crate::error::Status::DeviceBusy`] while it is
+    /// still open. If opening fails, this handle remains closed: it has no options,
+    /// and starting a scan or reading the parameters fails with
+    /// [`Inval`][`crate::error::Status::Inval`] until reconnecting succeeds.
+    ///
+    /// Note that all option values are reset by the backend. The caller is
+    /// responsible for restoring them.
+    pub fn reconnect(&mut self) -> Result<(), Error> {
+        let inner = &mut self.inner;
+        self.scanning = false;
+        let was_closed = std::mem::replace(&mut inner.closed, true);
+        let handle = inner.sane.with_sane(|sane| {
+            if !was_closed {
+                // SAFETY: call is synchronized and the handle is marked as closed above.
+                unsafe { sane.sys_close(inner.handle) };
+            }
+            // SAFETY: call is synchronized.
+            unsafe { sane.sys_open(inner.name.borrow()) }
+        })?;
+        inner.handle = handle;
+        inner.closed = false;
+        // The parameters read from the old connection are outdated regardless, see
+        // `parameters_dirty`.
+        *inner.params_dirty.get_mut() = false;
+        Ok(())
+    }
+
+    /// Name of the device as it was passed when connecting. This is empty if the
+    /// device was opened using [`Sane::connect_first`].
+    pub fn name(&self) -> &SaneStr {
+        self.inner.name.borrow()
+    }
+
+    /// Whether the device was closed because [reconnecting][`Self::reconnect`] failed.
+    /// A closed handle has no options, and starting a scan fails with
+    /// [`Inval`][`crate::error::Status::Inval`].
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+
+    /// Whether an option was set since the parameters were last read, using
+    /// [`Self::get_parameters`] or while scanning, which reported [`RELOAD_PARAMS`][`ControlInfo::RELOAD_PARAMS`]. In this case, previously
+    /// read parameters, such as an estimate of the image size, are outdated and need to
+    /// be read again.
+    ///
+    /// The flag is cleared when the device is [reconnected][`Self::reconnect`], as it
+    /// only tracks changes on the current connection. Parameters read before
+    /// reconnecting must always be read again, as all options are reset.
+    pub fn parameters_dirty(&self) -> bool {
+        self.inner.params_dirty.load(Ordering::Relaxed)
+    }
+
+    /// Description of the device, if the connection was established using
+    /// [`Sane::connect_described`].
+    pub fn description(&self) -> Option<&DeviceDescription> {
+        self.description.as_ref()
+    }
 }
 
 impl<A> Sane<A> {
+    /// Establishes a connection to the device with the given name.
+    ///
+    /// # Errors
+    /// - [`DeviceBusy`][`crate::error::Status::DeviceBusy`]: The device is currently busy (in use by somebody else).
+    /// - [`Inval`][`crate::error::Status::Inval`]: The device name is not valid.
+    /// - [`IoError`][`crate::error::Status::IoError`]: An error occurred while communicating with the device.
+    /// - [`NoMem`][`crate::error::Status::NoMem`]: An insufficient amount of memory is available.
+    /// - [`AccessDenied`][`crate::error::Status::AccessDenied`]: Access to the device has been denied due to insufficient or invalid authentication.
     pub fn connect(
         &self,
         devicename: &(impl AsRef<SaneStr> + ?Sized),
@@ -104,19 +333,83 @@ impl<A> Sane<A> {
         Self::connect_with(self, devicename)
     }
 
+    /// Connects to the first available device.
+    ///
+    /// # Errors
+    /// - [`Inval`][`crate::error::Status::Inval`]: No device is available.
+    /// - [`DeviceBusy`][`crate::error::Status::DeviceBusy`]: The device is currently busy (in use by somebody else).
+    /// - [`IoError`][`crate::error::Status::IoError`]: An error occurred while communicating with the device.
+    /// - [`NoMem`][`crate::error::Status::NoMem`]: An insufficient amount of memory is available.
+    /// - [`AccessDenied`][`crate::error::Status::AccessDenied`]: Access to the device has been denied due to insufficient or invalid authentication.
+    pub fn connect_first(&self) -> Result<DeviceHandle<&Self>, Error> {
+        // An empty device name opens the first available device by specification.
+        Self::connect_with(self, SaneStr::EMPTY)
+    }
+
+    /// Connects to the described device and keeps a copy of the description, which
+    /// can be accessed using [`DeviceHandle::description`].
+    ///
+    /// # Errors
+    /// See [`Self::connect`].
+    pub fn connect_described(
+        &self,
+        description: &DeviceDescription,
+    ) -> Result<DeviceHandle<&Self>, Error> {
+        let mut device = Self::connect_with(self, description.name())?;
+        device.description = Some(description.clone());
+        Ok(device)
+    }
+
+    /// Establishes a connection to the device with the given name, which shares
+    /// ownership of `sane`. The lock `L` can be any synchronization primitive
+    /// implementing [`WithSane`], such as a [`std::sync::Mutex`] or its `parking_lot`
+    /// counterpart.
+    ///
+    /// The handle is fully owned and can be moved to another thread, for example to
+    /// scan in the background, if `L` is [`Send`] and [`Sync`]. This is the case for
+    /// `Arc<Mutex<Sane<A>>>` if `A` is [`Send`].
+    ///
+    /// # Errors
+    /// See [`Self::connect`].
+    pub fn connect_shared<L: WithSane<Auth = A>>(
+        sane: &Arc<L>,
+        devicename: &(impl AsRef<SaneStr> + ?Sized),
+    ) -> Result<DeviceHandle<Arc<L>>, Error> {
+        Self::connect_with(Arc::clone(sane), devicename)
+    }
+
     pub fn connect_with<S: WithSane<Auth = A>>(
         with: S,
         devicename: &(impl AsRef<SaneStr> + ?Sized),
     ) -> Result<DeviceHandle<S>, Error> {
+        let devicename = devicename.as_ref();
         // SAFETY: call is synchronized.
-        let handle = with.with_sane(|sane| unsafe { sane.sys_open(devicename.as_ref()) })?;
+        let handle = with.with_sane(|sane| unsafe { sane.sys_open(devicename) })?;
 
         Ok(DeviceHandle {
-            inner: RawDeviceHandle { handle, sane: with },
+            inner: RawDeviceHandle {
+                handle,
+                sane: with,
+                name: devicename.to_owned(),
+                params_dirty: AtomicBool::new(false),
+                closed: false,
+            },
+            scanning: false,
+            description: None,
         })
     }
 }
 
+impl<S: WithSane> fmt::Debug for DeviceHandle<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(stringify!(DeviceHandle))
+            .field("name", &self.name())
+            .field("scanning", &self.scanning)
+            .field("description", &self.description)
+            .finish_non_exhaustive()
+    }
+}
+
 impl<S: WithSane> WithSane for DeviceHandle<S> {
     type Auth = S::Auth;
 
@@ -124,3 +417,44 @@ impl<S: WithSane> WithSane for DeviceHandle<S> {
         self.inner.with_sane(cb)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::{init_exit::TEST_INSTANCE_LOCK, NoAuth};
+
+    /// Connects to the `test` backend shipped with SANE, then reads the parameters and
+    /// scans an image on another thread.
+    fn scan_on_thread<L>(sane: L)
+    where
+        L: WithSane<Auth = NoAuth> + Send + Sync + 'static,
+    {
+        let sane = Arc::new(sane);
+        let device = Sane::connect_shared(&sane, SaneStr::from_cstr(c"test:0")).unwrap();
+        let worker = thread::spawn(move || {
+            let params = device.get_parameters().unwrap();
+            let image = device.scan_blocking().images().next().unwrap().unwrap();
+            assert_eq!(image.width, params.pixels_per_line);
+        });
+        worker.join().unwrap();
+    }
+
+    #[test]
+    #[ignore = "requires the SANE test backend"]
+    fn connect_shared_scans_on_thread() {
+        let _lock = TEST_INSTANCE_LOCK.lock();
+        let (sane, _) = Sane::init_no_auth().unwrap();
+        scan_on_thread(std::sync::Mutex::new(sane));
+    }
+
+    #[test]
+    #[ignore = "requires the SANE test backend"]
+    #[cfg(feature = "parking_lot")]
+    fn connect_shared_scans_on_thread_parking_lot() {
+        let _lock = TEST_INSTANCE_LOCK.lock();
+        let (sane, _) = Sane::init_no_auth().unwrap();
+        scan_on_thread(parking_lot::Mutex::new(sane));
+    }
+}
@@ -1,9 +1,17 @@
 use core::fmt;
-use std::{iter::FusedIterator, marker::PhantomData, ptr::NonNull};
+use std::{
+    collections::BTreeMap, iter::FusedIterator, marker::PhantomData, ops::Deref, ptr::NonNull,
+    slice, vec,
+};
 
-use crate::{slice_util::boxed_slice_from_fn, sys, Error, Sane, SaneStr};
+use crate::{slice_util::boxed_slice_from_fn, sys, DeviceHandle, Error, Sane, SaneStr, SaneString};
 
-#[derive(Clone)]
+/// Description of a device as reported by the backend.
+///
+/// Descriptions are ordered lexicographically by the Latin-1 bytes of their
+/// [name][`Self::name`], so a list of devices can be sorted for presentation.
+// The derived ordering compares `buf` first, which starts with the NUL-terminated name.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DeviceDescription {
     /// This buffer contains four C-Strings:
     /// - 0..name_end: name
@@ -160,6 +168,67 @@ impl Iterator for DeviceDescriptionIter<'_> {
 
 impl FusedIterator for DeviceDescriptionIter<'_> {}
 
+/// An owned snapshot of the available devices, obtained from [`Sane::get_device_list`].
+///
+/// Unlike [`DeviceDescriptionIter`], the list stays valid after the next call to
+/// [`sys_get_devices`][`libsane_sys::sane_get_devices`] and can be iterated repeatedly.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceList {
+    devices: Vec<DeviceDescription>,
+}
+
+impl DeviceList {
+    pub fn iter(&self) -> slice::Iter<'_, DeviceDescription> {
+        self.devices.iter()
+    }
+
+    pub fn as_slice(&self) -> &[DeviceDescription] {
+        &self.devices
+    }
+
+    pub fn into_vec(self) -> Vec<DeviceDescription> {
+        self.devices
+    }
+}
+
+impl Deref for DeviceList {
+    type Target = [DeviceDescription];
+
+    fn deref(&self) -> &Self::Target {
+        &self.devices
+    }
+}
+
+impl From<Vec<DeviceDescription>> for DeviceList {
+    fn from(devices: Vec<DeviceDescription>) -> Self {
+        Self { devices }
+    }
+}
+
+impl From<DeviceList> for Vec<DeviceDescription> {
+    fn from(list: DeviceList) -> Self {
+        list.devices
+    }
+}
+
+impl IntoIterator for DeviceList {
+    type Item = DeviceDescription;
+    type IntoIter = vec::IntoIter<DeviceDescription>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.devices.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a DeviceList {
+    type Item = &'a DeviceDescription;
+    type IntoIter = slice::Iter<'a, DeviceDescription>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.devices.iter()
+    }
+}
+
 impl<A> Sane<A> {
     fn get_devices<R: 'static>(
         &self,
@@ -177,10 +246,45 @@ impl<A> Sane<A> {
         self.get_devices(local_only, |it| it.to_vec())
     }
 
+    /// Takes a snapshot of the available devices, which can be stored and iterated
+    /// repeatedly.
+    pub fn get_device_list(&self, local_only: bool) -> Result<DeviceList, Error> {
+        self.get_devices_as_vec(local_only).map(DeviceList::from)
+    }
+
+    /// Connects to the device at `index` in the list of available devices, as it would
+    /// be returned by [`Self::get_devices_as_vec`].
+    ///
+    /// # Errors
+    /// - [`Inval`][`crate::error::Status::Inval`]: There is no device at `index`.
+    /// - See [`Self::connect`] for other errors.
+    pub fn connect_by_index(
+        &self,
+        local_only: bool,
+        index: usize,
+    ) -> Result<DeviceHandle<&Self>, Error> {
+        let description = self
+            .get_devices(local_only, |mut it| it.nth(index))?
+            .ok_or(Error::from_sys_status(sys::Status::Inval))?;
+        self.connect_described(&description)
+    }
+
     pub fn get_devices_as_boxed_slice(
         &self,
         local_only: bool,
     ) -> Result<Box<[DeviceDescription]>, Error> {
         self.get_devices(local_only, |it| it.to_boxed_slice())
     }
+
+    /// Collects the available devices into a map keyed by their [name][`DeviceDescription::name`].
+    /// Device names are unique, as they are used to [connect][`Self::connect`] to a device.
+    pub fn get_devices_map(
+        &self,
+        local_only: bool,
+    ) -> Result<BTreeMap<SaneString, DeviceDescription>, Error> {
+        self.get_devices(local_only, |it| {
+            it.map(|device| (device.name().to_owned(), device))
+                .collect()
+        })
+    }
 }
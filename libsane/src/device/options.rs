@@ -1,17 +1,29 @@
+pub mod standard;
+
 use core::fmt;
-use std::ffi::c_void;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::c_void,
+    hash::{Hash, Hasher},
+};
 
 use bitflags::bitflags;
 
 use crate::{
-    list::{new_word_list, SaneStrListIter},
-    sys, ControlInfo, DeviceHandle, Error, Fixed, OwnedValue, SaneStr, SaneString, Value,
-    ValueType, WithSane,
+    list::{new_word_list, word_list_len, SaneStrListIter},
+    sys, ControlInfo, DeviceHandle, Error, Fixed, OwnedValue, SaneStr, SaneString, Unit, Value,
+    ValueType, WithSane, WrongValueType,
 };
 
 use super::RawDeviceHandle;
 
-#[derive(Clone, Copy)]
+pub use standard::{ScanMode, StandardOptions};
+
+/// Maximum number of entries of a word list constraint accepted by
+/// [`DeviceOption::constraint_checked`]. Real backends list at most a few hundred
+/// values, a longer list indicates a corrupted descriptor.
+pub const MAX_CONSTRAINT_LIST_LEN: usize = 0x1_0000;
+
 pub struct DeviceOption<'a, S: WithSane> {
     raw: &'a RawDeviceHandle<S>,
     descriptor: *const sys::OptionDescriptor,
@@ -31,6 +43,24 @@ impl<'a, S: WithSane> DeviceOption<'a, S> {
         }
     }
 
+    /// Index of this option, which can be used to access it again using
+    /// [`DeviceHandle::option`].
+    pub const fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Fetches the descriptor of the option with the same [index][`Self::index`] again,
+    /// which is necessary after setting another option reported
+    /// [`RELOAD_OPTIONS`][`ControlInfo::RELOAD_OPTIONS`]. Returns `None` if the option
+    /// no longer exists.
+    ///
+    /// The backend may rearrange its options, so the reloaded option is not guaranteed
+    /// to have the same name. Finding it again using [`DeviceHandle::option_by_name`]
+    /// may be safer.
+    pub fn reloaded(self) -> Option<DeviceOption<'a, S>> {
+        self.raw.get_option(self.index)
+    }
+
     pub fn name(&self) -> &SaneStr {
         self.raw
             // SAFETY: reading is synchronized, and the device has not been closed. By spec, this is a valid C-String.
@@ -40,7 +70,7 @@ impl<'a, S: WithSane> DeviceOption<'a, S> {
     pub fn title(&self) -> &SaneStr {
         self.raw
             // SAFETY: reading is synchronized, and the device has not been closed. By spec, this is a valid C-String.
-            .with_sane(|_| unsafe { SaneStr::from_ptr((*self.descriptor).name) })
+            .with_sane(|_| unsafe { SaneStr::from_ptr((*self.descriptor).title) })
     }
 
     pub fn description(&self) -> &SaneStr {
@@ -53,12 +83,20 @@ impl<'a, S: WithSane> DeviceOption<'a, S> {
         ValueType::from(self.sys_type())
     }
 
+    /// Raw type of the option as reported by the backend, which may not be known to
+    /// [`ValueType`].
     pub fn sys_type(&self) -> sys::ValueType {
         // SAFETY: reading is synchronized, and the device has not been closed.
         self.raw.with_sane(|_| unsafe { (*self.descriptor).type_ })
     }
 
-    pub fn unit(&self) -> sys::Unit {
+    pub fn unit(&self) -> Unit {
+        Unit::from(self.sys_unit())
+    }
+
+    /// Raw unit of the option as reported by the backend, which may not be known to
+    /// [`Unit`].
+    pub fn sys_unit(&self) -> sys::Unit {
         // SAFETY: reading is synchronized, and the device has not been closed.
         self.raw.with_sane(|_| unsafe { (*self.descriptor).unit })
     }
@@ -79,68 +117,151 @@ impl<'a, S: WithSane> DeviceOption<'a, S> {
     pub fn constraint(&self) -> Option<DeviceOptionConstraint> {
         self.raw.with_sane(|_| {
             // SAFETY: reading is synchronized, and the device has not been closed.
-            let ctype = unsafe { (*self.descriptor).constraint_type };
+            let descriptor = unsafe { &*self.descriptor };
+            // SAFETY: The descriptor was provided by the backend, which adheres to the spec.
+            unsafe { Self::read_constraint(descriptor) }
+        })
+    }
+
+    /// Like [`Self::constraint`], but copies the constraint so that it can outlive this
+    /// option.
+    pub fn owned_constraint(&self) -> Option<OwnedConstraint> {
+        self.constraint().map(OwnedConstraint::from)
+    }
+
+    /// Like [`Self::constraint`], but validates the descriptor first, which protects
+    /// against malformed descriptors of misbehaving backends.
+    ///
+    /// Note that the length of a word list cannot be cross-checked against
+    /// [`Self::size`], as the size refers to the value of the option and not to its
+    /// constraint. Instead, it is bounded by [`MAX_CONSTRAINT_LIST_LEN`].
+    ///
+    /// # Errors
+    /// - [`Inval`][`crate::error::Status::Inval`]: The size of a word-sized option is not a
+    ///   positive multiple of the word size, the constraint points to no data, a word list
+    ///   has a negative length or is longer than [`MAX_CONSTRAINT_LIST_LEN`], or a range
+    ///   has a minimum greater than its maximum or a negative quantization.
+    pub fn constraint_checked(&self) -> Result<Option<DeviceOptionConstraint>, Error> {
+        let invalid = || Error::from_sys_status(sys::Status::Inval);
+        self.raw.with_sane(|_| {
             // SAFETY: reading is synchronized, and the device has not been closed.
-            let value_type = unsafe { (*self.descriptor).type_ };
-            match ctype {
-                sys::ConstraintType::None => None,
-                sys::ConstraintType::Range => Some({
+            let descriptor = unsafe { &*self.descriptor };
+            if ValueType::from(descriptor.type_).is_word_sized() {
+                let word_size = std::mem::size_of::<sys::Word>() as sys::Int;
+                if descriptor.size <= 0 || descriptor.size % word_size != 0 {
+                    return Err(invalid());
+                }
+            }
+            match descriptor.constraint_type {
+                sys::ConstraintType::Range => {
                     // SAFETY: By the spec, the union has a value of range
-                    let r = unsafe { &*(*self.descriptor).constraint.range };
-                    match value_type {
-                        sys::ValueType::Int => DeviceOptionConstraint::RangeInt {
-                            min: r.min,
-                            max: r.max,
-                            quant: r.quant,
-                        },
-                        sys::ValueType::Fixed => DeviceOptionConstraint::RangeFixed {
-                            min: Fixed::from_bits(r.min),
-                            max: Fixed::from_bits(r.max),
-                            quant: Fixed::from_bits(r.quant),
-                        },
-                        other => DeviceOptionConstraint::Unsupported {
-                            value_type: other,
-                            contraint_type: sys::ConstraintType::Range,
-                        },
+                    let range = unsafe { descriptor.constraint.range };
+                    if range.is_null() || !range.is_aligned() {
+                        return Err(invalid());
                     }
-                }),
-                sys::ConstraintType::WordList => Some({
-                    // SAFETY: By the spec, the union has a value of word_list
-                    let data = unsafe { (*self.descriptor).constraint.word_list };
-                    match value_type {
-                        sys::ValueType::Int => {
-                            DeviceOptionConstraint::ListInt(
-                                // SAFETY: by spec https://sane-project.gitlab.io/standard/api.html#option-value-constraints
-                                unsafe { new_word_list::<sys::Int>(data) },
-                            )
-                        }
-                        sys::ValueType::Fixed => DeviceOptionConstraint::ListFixed(
-                            // SAFETY: by spec https://sane-project.gitlab.io/standard/api.html#option-value-constraints
-                            unsafe { new_word_list::<Fixed>(data) },
-                        ),
-                        other => DeviceOptionConstraint::Unsupported {
-                            value_type: other,
-                            contraint_type: sys::ConstraintType::WordList,
-                        },
+                    // SAFETY: The pointer was checked above and points to a range by spec.
+                    let range = unsafe { &*range };
+                    if range.min > range.max || range.quant < 0 {
+                        return Err(invalid());
                     }
-                }),
-                sys::ConstraintType::StringList => Some({
+                }
+                sys::ConstraintType::WordList => {
+                    // SAFETY: By the spec, the union has a value of word_list, which is
+                    // checked for null and alignment before being read.
+                    let len = unsafe { word_list_len(descriptor.constraint.word_list) }
+                        .ok_or_else(invalid)?;
+                    if len > MAX_CONSTRAINT_LIST_LEN {
+                        return Err(invalid());
+                    }
+                }
+                sys::ConstraintType::StringList => {
                     // SAFETY: By the spec, the union has a value of string_list
-                    let data = unsafe { (*self.descriptor).constraint.string_list };
-                    DeviceOptionConstraint::ListString(
-                        // SAFETY: by spec, this is a null-terminated pointer list
-                        // https://sane-project.gitlab.io/standard/api.html#option-value-constraints
-                        unsafe { SaneStrListIter::new(data) },
-                    )
-                }),
-                other => Some(DeviceOptionConstraint::Unsupported {
-                    value_type,
-                    contraint_type: other,
-                }),
+                    let list = unsafe { descriptor.constraint.string_list };
+                    if list.is_null() {
+                        return Err(invalid());
+                    }
+                }
+                _ => {}
             }
+            // SAFETY: The constraint was validated above, and the descriptor is read under
+            // the same lock, so it cannot have changed in the meantime.
+            Ok(unsafe { Self::read_constraint(descriptor) })
         })
     }
 
+    /// Reads the constraint of the descriptor.
+    ///
+    /// # Safety
+    /// The constraint of the descriptor must be valid as specified by the standard.
+    unsafe fn read_constraint(
+        descriptor: &sys::OptionDescriptor,
+    ) -> Option<DeviceOptionConstraint<'_>> {
+        let ctype = descriptor.constraint_type;
+        let value_type = descriptor.type_;
+        match ctype {
+            sys::ConstraintType::None => None,
+            sys::ConstraintType::Range => Some({
+                // SAFETY: By the spec, the union has a value of range
+                let r = unsafe { &*descriptor.constraint.range };
+                match value_type {
+                    sys::ValueType::Int => DeviceOptionConstraint::RangeInt {
+                        min: r.min,
+                        max: r.max,
+                        quant: r.quant,
+                    },
+                    sys::ValueType::Fixed => DeviceOptionConstraint::RangeFixed {
+                        min: Fixed::from_bits(r.min),
+                        max: Fixed::from_bits(r.max),
+                        quant: Fixed::from_bits(r.quant),
+                    },
+                    other => DeviceOptionConstraint::Unsupported {
+                        value_type: other,
+                        contraint_type: sys::ConstraintType::Range,
+                    },
+                }
+            }),
+            sys::ConstraintType::WordList => Some({
+                // SAFETY: By the spec, the union has a value of word_list
+                let data = unsafe { descriptor.constraint.word_list };
+                match value_type {
+                    sys::ValueType::Int => {
+                        DeviceOptionConstraint::ListInt(
+                            // SAFETY: by spec https://sane-project.gitlab.io/standard/api.html#option-value-constraints
+                            unsafe { new_word_list::<sys::Int>(data) },
+                        )
+                    }
+                    sys::ValueType::Fixed => DeviceOptionConstraint::ListFixed(
+                        // SAFETY: by spec https://sane-project.gitlab.io/standard/api.html#option-value-constraints
+                        unsafe { new_word_list::<Fixed>(data) },
+                    ),
+                    other => DeviceOptionConstraint::Unsupported {
+                        value_type: other,
+                        contraint_type: sys::ConstraintType::WordList,
+                    },
+                }
+            }),
+            sys::ConstraintType::StringList => Some({
+                // SAFETY: By the spec, the union has a value of string_list
+                let data = unsafe { descriptor.constraint.string_list };
+                DeviceOptionConstraint::ListString(
+                    // SAFETY: by spec, this is a null-terminated pointer list
+                    // https://sane-project.gitlab.io/standard/api.html#option-value-constraints
+                    unsafe { SaneStrListIter::new(data) },
+                )
+            }),
+            other => Some(DeviceOptionConstraint::Unsupported {
+                value_type,
+                contraint_type: other,
+            }),
+        }
+    }
+
+    /// Reads the current value of this option. Returns `None` if the option has no
+    /// value, such as a [button][`ValueType::Button`] or [group][`ValueType::Group`].
+    ///
+    /// String values are read into a buffer of [`Self::size`] bytes. Some backends
+    /// report a size of zero, in which case a buffer of one byte is used, which
+    /// results in an empty string.
     pub fn get(&mut self) -> Result<Option<OwnedValue>, Error> {
         self.raw.with_sane(|sane| {
             // SAFETY: reading is synchronized, and the device has not been closed.
@@ -158,7 +279,7 @@ impl<'a, S: WithSane> DeviceOption<'a, S> {
                 }?;
                 Ok(OwnedValue::from_word(val, ty))
             } else if ty == ValueType::String {
-                let mut strbuf = SaneString::with_capacity(self.size());
+                let mut strbuf = string_buffer(self.size());
                 // SAFETY: Device is not closed, call is synchronized, strbuf has required capacity.
                 unsafe {
                     sane.sys_get_option_value(
@@ -193,12 +314,15 @@ impl<'a, S: WithSane> DeviceOption<'a, S> {
                         (&mut val) as *mut _ as *mut c_void,
                     )
                 }?;
-                Ok((info, OwnedValue::from_word(val, ty).unwrap()))
+                self.raw.option_changed(info);
+                Ok((info, word_to_value(val, ty)?))
             } else if let Value::String(s) = value {
                 // The documentation doesn't technically require allocating extra space,
                 // but this is to be safe.
-                let mut strbuf = SaneString::with_capacity(self.size());
-                strbuf.set_contents(s);
+                let mut strbuf = string_buffer(self.size());
+                strbuf
+                    .try_set_contents(s)
+                    .map_err(|_| Error::from_sys_status(sys::Status::Inval))?;
                 // SAFETY: Device is not closed, call is synchronized.
                 let info = unsafe {
                     sane.sys_set_option_value(
@@ -207,6 +331,7 @@ impl<'a, S: WithSane> DeviceOption<'a, S> {
                         strbuf.as_mut_ptr() as *mut c_void,
                     )
                 }?;
+                self.raw.option_changed(info);
                 Ok((info, OwnedValue::String(strbuf)))
             } else {
                 unreachable!()
@@ -214,10 +339,115 @@ impl<'a, S: WithSane> DeviceOption<'a, S> {
         })
     }
 
-    pub fn set_auto(&self) -> Result<(), Error> {
-        self.raw
+    /// Suggests an initial value for this option, for example to populate a settings
+    /// panel. SANE has no notion of a default value, so this is only a heuristic:
+    ///
+    /// 1. The current value, if the option is active and can be read.
+    /// 2. The middle of a range, rounded down to a step of its quantization.
+    /// 3. The first entry of a list.
+    ///
+    /// Returns `None` if none of these apply, such as for an inactive option without a
+    /// constraint.
+    pub fn suggested_default(&self) -> Option<OwnedValue> {
+        let caps = self.capabilities();
+        if caps.is_active() && caps.is_detectable() {
+            // Reading the value does not modify the descriptor, but `get` takes `&mut self`.
+            let mut this = *self;
+            if let Ok(Some(value)) = this.get() {
+                return Some(value);
+            }
+        }
+        let midpoint = |min: i32, max: i32, quant: i32| {
+            let (min, max, quant) = (min as i64, max as i64, quant as i64);
+            let mut mid = min + (max - min) / 2;
+            if quant > 0 {
+                mid = min + (mid - min) / quant * quant;
+            }
+            // The midpoint lies between min and max, which are both `i32`.
+            mid as i32
+        };
+        match self.constraint()? {
+            DeviceOptionConstraint::RangeInt { min, max, quant } => {
+                Some(OwnedValue::Int(midpoint(min, max, quant)))
+            }
+            DeviceOptionConstraint::RangeFixed { min, max, quant } => Some(OwnedValue::Fixed(
+                Fixed::from_bits(midpoint(min.to_bits(), max.to_bits(), quant.to_bits())),
+            )),
+            DeviceOptionConstraint::ListInt(list) => list.first().copied().map(OwnedValue::Int),
+            DeviceOptionConstraint::ListFixed(list) => list.first().copied().map(OwnedValue::Fixed),
+            DeviceOptionConstraint::ListString(mut list) => {
+                list.next().map(|item| OwnedValue::String(item.to_owned()))
+            }
+            DeviceOptionConstraint::Unsupported { .. } => None,
+        }
+    }
+
+    /// Like [`Self::set`], but returns a [`SetOutcome`] which summarizes what a frontend
+    /// needs to update after setting the value, such as the value that was actually
+    /// applied by the backend.
+    pub fn set_detailed(&mut self, value: Value) -> Result<SetOutcome, Error> {
+        let (info, applied) = self.set(value)?;
+        Ok(SetOutcome {
+            requested: value.into(),
+            applied,
+            inexact: info.is_inexact(),
+            reload_options: info.needs_reload_options(),
+            reload_params: info.needs_reload_params(),
+        })
+    }
+
+    /// Like [`Self::set`], but validates the value against the [constraint][`Self::constraint`]
+    /// of this option before passing it to the backend. Values within a range are not
+    /// checked for quantization, they are rounded by the backend instead.
+    pub fn set_checked(&mut self, value: Value) -> Result<(ControlInfo, OwnedValue), SetError> {
+        let ty = self.type_();
+        if value.type_of() != ty {
+            return Err(SetError::WrongType(WrongValueType {
+                expected: ty,
+                got: value.type_of(),
+            }));
+        }
+        if let Some(constraint) = self.constraint() {
+            constraint.check(value)?;
+        }
+        Ok(self.set(value)?)
+    }
+
+    /// Lets the backend select a value automatically. Returns how the change affected
+    /// other options and the scan parameters, like [`Self::set`].
+    pub fn set_auto(&self) -> Result<ControlInfo, Error> {
+        let info = self
+            .raw
             // SAFETY: Device is not closed, call is synchronized.
-            .with_sane(|sane| unsafe { sane.sys_set_option_auto(self.raw.handle, self.index) })
+            .with_sane(|sane| unsafe { sane.sys_set_option_auto(self.raw.handle, self.index) })?;
+        self.raw.option_changed(info);
+        Ok(info)
+    }
+}
+
+/// Options are equal if they have the same index and belong to the same device handle.
+// Implemented manually, as deriving would require `S: Copy`, which is not needed to copy
+// the reference to the device.
+impl<S: WithSane> Clone for DeviceOption<'_, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S: WithSane> Copy for DeviceOption<'_, S> {}
+
+impl<S: WithSane> PartialEq for DeviceOption<'_, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.raw.handle == other.raw.handle
+    }
+}
+
+impl<S: WithSane> Eq for DeviceOption<'_, S> {}
+
+impl<S: WithSane> Hash for DeviceOption<'_, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.raw.handle.hash(state);
     }
 }
 
@@ -250,6 +480,91 @@ bitflags! {
     }
 }
 
+impl DeviceOptionCapabilities {
+    /// The option is active and thus meaningful. Options may become inactive
+    /// depending on the value of other options.
+    pub const fn is_active(&self) -> bool {
+        !self.contains(Self::CAP_INACTIVE)
+    }
+
+    /// The option value can be set by a call to [`DeviceOption::set`].
+    pub const fn is_settable(&self) -> bool {
+        self.contains(Self::SOFT_SELECT)
+    }
+
+    /// The option value can be set by user-intervention, such as flipping a switch on
+    /// the device.
+    pub const fn is_hardware_select(&self) -> bool {
+        self.contains(Self::HARD_SELECT)
+    }
+
+    /// The option value can be read by a call to [`DeviceOption::get`].
+    pub const fn is_detectable(&self) -> bool {
+        self.contains(Self::CAP_SOFT_DETECT)
+    }
+
+    /// The option is not directly supported by the device and is instead emulated
+    /// by the backend.
+    pub const fn is_emulated(&self) -> bool {
+        self.contains(Self::CAP_EMULATED)
+    }
+
+    /// The backend can select a reasonable value automatically, see
+    /// [`DeviceOption::set_auto`].
+    pub const fn supports_auto(&self) -> bool {
+        self.contains(Self::CAP_AUTOMATIC)
+    }
+
+    /// The option should be considered an advanced feature, which a frontend may
+    /// choose to hide.
+    pub const fn is_advanced(&self) -> bool {
+        self.contains(Self::CAP_ADVANCED)
+    }
+}
+
+/// The action performed by `sane_control_option`, for use with
+/// [`DeviceHandle::with_raw_handle`]. [`DeviceOption`] provides safe wrappers for each
+/// action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptionAction {
+    /// Reads the current value of the option, see [`DeviceOption::get`].
+    GetValue,
+    /// Sets the value of the option, see [`DeviceOption::set`].
+    SetValue,
+    /// Lets the backend select a value automatically, see [`DeviceOption::set_auto`].
+    SetAuto,
+}
+
+impl OptionAction {
+    pub const fn to_sys(&self) -> sys::Action {
+        match self {
+            Self::GetValue => sys::Action::GetValue,
+            Self::SetValue => sys::Action::SetValue,
+            Self::SetAuto => sys::Action::SetAuto,
+        }
+    }
+}
+
+impl From<OptionAction> for sys::Action {
+    fn from(value: OptionAction) -> Self {
+        value.to_sys()
+    }
+}
+
+impl TryFrom<sys::Action> for OptionAction {
+    type Error = sys::Action;
+
+    /// Fails with the given action if it is unknown.
+    fn try_from(value: sys::Action) -> Result<Self, Self::Error> {
+        match value {
+            sys::Action::GetValue => Ok(Self::GetValue),
+            sys::Action::SetValue => Ok(Self::SetValue),
+            sys::Action::SetAuto => Ok(Self::SetAuto),
+            other => Err(other),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum DeviceOptionConstraint<'a> {
     RangeInt {
@@ -265,18 +580,249 @@ pub enum DeviceOptionConstraint<'a> {
     ListInt(&'a [sys::Int]),
     ListFixed(&'a [Fixed]),
     ListString(SaneStrListIter<'a>),
+    /// The combination of value and constraint type is not known to these bindings,
+    /// therefore the raw types reported by the backend are provided.
+    Unsupported {
+        value_type: sys::ValueType,
+        contraint_type: sys::ConstraintType,
+    },
+}
+
+impl<'a> DeviceOptionConstraint<'a> {
+    /// Number of legal values of this constraint, which is the number of list entries
+    /// or the number of steps of `quant` in a range. Returns `None` for continuous ranges
+    /// (with a `quant` of zero), like [`Self::iter_values`].
+    pub fn len(&self) -> Option<usize> {
+        let range_len = |min: i32, max: i32, quant: i32| {
+            (quant > 0).then(|| {
+                let steps = (max as i64 - min as i64).div_euclid(quant as i64);
+                (steps + 1).max(0) as usize
+            })
+        };
+        match self {
+            Self::RangeInt { min, max, quant } => range_len(*min, *max, *quant),
+            Self::RangeFixed { min, max, quant } => {
+                range_len(min.to_bits(), max.to_bits(), quant.to_bits())
+            }
+            Self::ListInt(list) => Some(list.len()),
+            Self::ListFixed(list) => Some(list.len()),
+            Self::ListString(list) => Some(list.len()),
+            Self::Unsupported { .. } => None,
+        }
+    }
+
+    /// Whether this constraint permits no value at all, see [`Self::len`].
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Iterates all legal values of this constraint. For ranges, every step of `quant`
+    /// from `min` to `max` is yielded. Returns `None` if the values cannot be enumerated,
+    /// which is the case for ranges with a `quant` of zero (continuous ranges).
+    pub fn iter_values(&self) -> Option<impl Iterator<Item = Value<'a>>> {
+        let values = match *self {
+            Self::RangeInt { min, max, quant } if quant > 0 => ConstraintValues::Range {
+                next: Some(min).filter(|min| *min <= max),
+                max,
+                quant,
+                ty: ValueType::Int,
+            },
+            Self::RangeFixed { min, max, quant } if quant.to_bits() > 0 => {
+                ConstraintValues::Range {
+                    next: Some(min.to_bits()).filter(|min| *min <= max.to_bits()),
+                    max: max.to_bits(),
+                    quant: quant.to_bits(),
+                    ty: ValueType::Fixed,
+                }
+            }
+            Self::ListInt(list) => ConstraintValues::ListInt(list.iter()),
+            Self::ListFixed(list) => ConstraintValues::ListFixed(list.iter()),
+            Self::ListString(ref list) => ConstraintValues::ListString(list.clone()),
+            _ => return None,
+        };
+        Some(values)
+    }
+}
+
+impl DeviceOptionConstraint<'_> {
+    fn check(&self, value: Value) -> Result<(), SetError> {
+        match (self, value) {
+            (Self::RangeInt { min, max, .. }, Value::Int(v)) if !(min..=max).contains(&&v) => {
+                Err(SetError::OutOfRange)
+            }
+            (Self::RangeFixed { min, max, .. }, Value::Fixed(v)) if !(min..=max).contains(&&v) => {
+                Err(SetError::OutOfRange)
+            }
+            (Self::ListInt(list), Value::Int(v)) if !list.contains(&v) => Err(SetError::NotInList),
+            (Self::ListFixed(list), Value::Fixed(v)) if !list.contains(&v) => {
+                Err(SetError::NotInList)
+            }
+            (Self::ListString(list), Value::String(v)) if !list.clone().any(|item| item == v) => {
+                Err(SetError::NotInList)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Owned form of a [`DeviceOptionConstraint`], which can be kept after the
+/// [`DeviceOption`] it was obtained from is released, for example to populate a
+/// drop-down list later. See [`DeviceOption::owned_constraint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OwnedConstraint {
+    RangeInt {
+        min: i32,
+        max: i32,
+        quant: i32,
+    },
+    RangeFixed {
+        min: Fixed,
+        max: Fixed,
+        quant: Fixed,
+    },
+    ListInt(Vec<i32>),
+    ListFixed(Vec<Fixed>),
+    ListString(Vec<SaneString>),
+    /// See [`DeviceOptionConstraint::Unsupported`].
     Unsupported {
         value_type: sys::ValueType,
         contraint_type: sys::ConstraintType,
     },
 }
 
+impl From<&DeviceOptionConstraint<'_>> for OwnedConstraint {
+    fn from(value: &DeviceOptionConstraint<'_>) -> Self {
+        match value {
+            DeviceOptionConstraint::RangeInt { min, max, quant } => Self::RangeInt {
+                min: *min,
+                max: *max,
+                quant: *quant,
+            },
+            DeviceOptionConstraint::RangeFixed { min, max, quant } => Self::RangeFixed {
+                min: *min,
+                max: *max,
+                quant: *quant,
+            },
+            DeviceOptionConstraint::ListInt(list) => Self::ListInt(list.to_vec()),
+            DeviceOptionConstraint::ListFixed(list) => Self::ListFixed(list.to_vec()),
+            DeviceOptionConstraint::ListString(list) => {
+                Self::ListString(list.clone().map(SaneStr::to_owned).collect())
+            }
+            DeviceOptionConstraint::Unsupported {
+                value_type,
+                contraint_type,
+            } => Self::Unsupported {
+                value_type: *value_type,
+                contraint_type: *contraint_type,
+            },
+        }
+    }
+}
+
+impl From<DeviceOptionConstraint<'_>> for OwnedConstraint {
+    fn from(value: DeviceOptionConstraint<'_>) -> Self {
+        Self::from(&value)
+    }
+}
+
+enum ConstraintValues<'a> {
+    Range {
+        next: Option<sys::Word>,
+        max: sys::Word,
+        quant: sys::Word,
+        ty: ValueType,
+    },
+    ListInt(std::slice::Iter<'a, sys::Int>),
+    ListFixed(std::slice::Iter<'a, Fixed>),
+    ListString(SaneStrListIter<'a>),
+}
+
+impl<'a> Iterator for ConstraintValues<'a> {
+    type Item = Value<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Range {
+                next,
+                max,
+                quant,
+                ty,
+            } => {
+                let current = (*next)?;
+                *next = current.checked_add(*quant).filter(|v| v <= max);
+                Value::from_word(current, *ty)
+            }
+            Self::ListInt(iter) => iter.next().map(|v| Value::Int(*v)),
+            Self::ListFixed(iter) => iter.next().map(|v| Value::Fixed(*v)),
+            Self::ListString(iter) => iter.next().map(Value::String),
+        }
+    }
+}
+
 impl<S: WithSane> DeviceHandle<S> {
     pub fn option(&mut self, index: u32) -> Option<DeviceOption<S>> {
         self.inner.get_option(index)
     }
 
+    /// Finds the option with the given name.
+    pub fn option_by_name(&mut self, name: &SaneStr) -> Option<DeviceOption<S>> {
+        self.options().find(|option| option.name() == name)
+    }
+
+    /// Iterates over all options of this device, excluding the option count at index 0.
+    pub fn options(&mut self) -> impl Iterator<Item = DeviceOption<S>> + '_ {
+        let count = self.option_count() as u32;
+        (1..count).filter_map(|index| self.inner.get_option(index))
+    }
+
+    /// Collects all options for which `pred` returns `true`.
+    pub fn options_filtered(
+        &mut self,
+        pred: impl Fn(&DeviceOption<S>) -> bool,
+    ) -> Vec<DeviceOption<S>> {
+        self.options().filter(|option| pred(option)).collect()
+    }
+
+    /// Collects all options which are not [advanced][`DeviceOptionCapabilities::is_advanced`],
+    /// i.e. those that should be shown to the user by default.
+    pub fn basic_options(&mut self) -> Vec<DeviceOption<S>> {
+        self.options_filtered(|option| !option.capabilities().is_advanced())
+    }
+
+    /// Collects all options which are [advanced][`DeviceOptionCapabilities::is_advanced`],
+    /// i.e. those that should only be shown to the user on request.
+    pub fn advanced_options(&mut self) -> Vec<DeviceOption<S>> {
+        self.options_filtered(|option| option.capabilities().is_advanced())
+    }
+
+    /// Groups the options by the [`Group`][`ValueType::Group`] options preceding them, which
+    /// act as section headers. Options before the first header are collected in a group with
+    /// an empty title, which is omitted if there are none.
+    pub fn option_groups(&mut self) -> Vec<OptionGroup> {
+        let mut groups = vec![OptionGroup {
+            title: SaneStr::EMPTY.to_owned(),
+            options: Vec::new(),
+        }];
+        for option in self.options() {
+            if option.type_().is_group() {
+                groups.push(OptionGroup {
+                    title: option.title().to_owned(),
+                    options: Vec::new(),
+                });
+            } else {
+                groups.last_mut().unwrap().options.push(option.index());
+            }
+        }
+        if groups[0].options.is_empty() {
+            groups.remove(0);
+        }
+        groups
+    }
+
     pub fn option_count(&mut self) -> usize {
+        if self.is_closed() {
+            return 0;
+        }
         let mut opt = self.option(0).expect("missing 0th option for count");
         debug_assert_eq!(opt.type_(), ValueType::Int);
         let Ok(Some(OwnedValue::Int(count))) = opt.get() else {
@@ -285,3 +831,193 @@ impl<S: WithSane> DeviceHandle<S> {
         count.try_into().unwrap()
     }
 }
+
+/// Result of [`DeviceOption::set_detailed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetOutcome {
+    /// The value that was passed to the backend.
+    pub requested: OwnedValue,
+    /// The value that was applied by the backend, which should be shown to the user.
+    pub applied: OwnedValue,
+    /// The backend rounded or otherwise adjusted the requested value, see
+    /// [`ControlInfo::is_inexact`].
+    pub inexact: bool,
+    /// The descriptors of other options need to be read again, see
+    /// [`ControlInfo::needs_reload_options`].
+    pub reload_options: bool,
+    /// The scan parameters need to be read again, see
+    /// [`ControlInfo::needs_reload_params`].
+    pub reload_params: bool,
+}
+
+/// Error returned by [`DeviceOption::set_checked`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetError {
+    /// The value has a different type than the option.
+    WrongType(WrongValueType),
+    /// The value is outside of the range permitted by the option.
+    OutOfRange,
+    /// The value is not one of the values permitted by the option.
+    NotInList,
+    /// The backend rejected the value.
+    Backend(Error),
+}
+
+impl fmt::Display for SetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongType(err) => fmt::Display::fmt(err, f),
+            Self::OutOfRange => f.write_str("value is out of range"),
+            Self::NotInList => f.write_str("value is not in the list of permitted values"),
+            Self::Backend(err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl std::error::Error for SetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::WrongType(err) => Some(err),
+            Self::Backend(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for SetError {
+    fn from(value: Error) -> Self {
+        Self::Backend(value)
+    }
+}
+
+/// A section of options, see [`DeviceHandle::option_groups`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionGroup {
+    pub title: SaneString,
+    /// Indices of the options in this group.
+    pub options: Vec<u32>,
+}
+
+impl<S: WithSane> DeviceHandle<S> {
+    /// Sets the value of every option in `config` by name. Options that do not exist on
+    /// this device, are inactive, cannot be set by software, or have a different type are
+    /// skipped. Returns the combined [`ControlInfo`] of all changes.
+    ///
+    /// Options are set in the order in which the device lists them. If setting an option
+    /// requires the options to be reloaded, the option list is traversed again.
+    pub fn apply_config(
+        &mut self,
+        config: &BTreeMap<SaneString, OwnedValue>,
+    ) -> Result<ControlInfo, Error> {
+        let mut info = ControlInfo::empty();
+        let mut applied = BTreeSet::<SaneString>::new();
+        'reload: loop {
+            let count = self.option_count() as u32;
+            for index in 1..count {
+                let Some(mut option) = self.option(index) else {
+                    continue;
+                };
+                let name = option.name();
+                if applied.contains(name) {
+                    continue;
+                }
+                let Some(value) = config.get(name) else {
+                    continue;
+                };
+                let caps = option.capabilities();
+                if !caps.is_active() || !caps.is_settable() || value.type_of() != option.type_() {
+                    continue;
+                }
+                applied.insert(name.to_owned());
+                let (set_info, _) = option.set(value.as_ref())?;
+                info |= set_info;
+                if set_info.contains(ControlInfo::RELOAD_OPTIONS) {
+                    continue 'reload;
+                }
+            }
+            return Ok(info);
+        }
+    }
+
+    /// Reads the values of all active options that can be set and read by software. The
+    /// result can be applied again using [`Self::apply_config`]. Buttons and groups have
+    /// no value and are skipped.
+    pub fn dump_config(&mut self) -> Result<BTreeMap<SaneString, OwnedValue>, Error> {
+        let mut config = BTreeMap::new();
+        let count = self.option_count() as u32;
+        for index in 1..count {
+            let Some(mut option) = self.option(index) else {
+                continue;
+            };
+            let caps = option.capabilities();
+            if !caps.is_active() || !caps.is_settable() || !caps.is_detectable() {
+                continue;
+            }
+            if !option.type_().is_value() {
+                continue;
+            }
+            if let Some(value) = option.get()? {
+                config.insert(option.name().to_owned(), value);
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Converts a word returned by the backend into a value of the given type.
+///
+/// The descriptor is provided by the backend and cannot be trusted, so a type mismatch
+/// is reported as [`Inval`][`crate::error::Status::Inval`] instead of panicking.
+fn word_to_value(word: sys::Word, ty: ValueType) -> Result<OwnedValue, Error> {
+    OwnedValue::from_word(word, ty).ok_or(Error::from_sys_status(sys::Status::Inval))
+}
+
+/// Allocates an empty buffer for a string option of the given size.
+///
+/// The size is provided by the backend and cannot be trusted. Some backends report a
+/// size of zero, in which case the buffer only fits the NUL terminator.
+fn string_buffer(size: usize) -> SaneString {
+    SaneString::with_capacity(size.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_buffer_of_size_zero() {
+        let mut buf = string_buffer(0);
+        assert_eq!(buf.capacity(), 1);
+        assert!(buf.is_empty());
+        assert_eq!(buf.to_bytes_with_nul(), b"\0");
+        assert!(buf.try_set_contents(SaneStr::from_cstr(c"")).is_ok());
+        assert!(buf.try_set_contents(SaneStr::from_cstr(c"a")).is_err());
+        assert!(buf.is_empty());
+
+        let buf = string_buffer(8);
+        assert_eq!(buf.capacity(), 8);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn word_to_value_rejects_non_word_types() {
+        for ty in [ValueType::String, ValueType::Button, ValueType::Group] {
+            assert_eq!(
+                word_to_value(1, ty).unwrap_err(),
+                Error::from_sys_status(sys::Status::Inval)
+            );
+        }
+        assert_eq!(
+            word_to_value(sys::TRUE as sys::Word, ValueType::Bool).unwrap(),
+            OwnedValue::Bool(true)
+        );
+        assert_eq!(
+            word_to_value(-7, ValueType::Int).unwrap(),
+            OwnedValue::Int(-7)
+        );
+        assert_eq!(
+            word_to_value(1 << 16, ValueType::Fixed).unwrap(),
+            OwnedValue::Fixed(Fixed::new(1.0))
+        );
+    }
+}
@@ -0,0 +1,452 @@
+use core::fmt;
+use std::borrow::Borrow;
+
+use super::DeviceOptionConstraint;
+use crate::{
+    sys, ControlInfo, DeviceHandle, Error, Fixed, OwnedValue, SaneStr, SaneString, Value,
+    ValueType, WithSane,
+};
+
+/// Names of well-known options, as defined by the SANE standard.
+///
+/// See <https://sane-project.gitlab.io/standard/1.06/api.html#well-known-options>
+pub mod name {
+    use crate::SaneStr;
+
+    pub const SCAN_MODE: &SaneStr = SaneStr::from_cstr(c"mode");
+    pub const PREVIEW: &SaneStr = SaneStr::from_cstr(c"preview");
+    pub const BRIGHTNESS: &SaneStr = SaneStr::from_cstr(c"brightness");
+    pub const CONTRAST: &SaneStr = SaneStr::from_cstr(c"contrast");
+    pub const RESOLUTION: &SaneStr = SaneStr::from_cstr(c"resolution");
+    pub const SCAN_SOURCE: &SaneStr = SaneStr::from_cstr(c"source");
+    pub const BIT_DEPTH: &SaneStr = SaneStr::from_cstr(c"depth");
+}
+
+/// Provides typed access to well-known options.
+/// Obtained via [`DeviceHandle::standard_options`].
+pub struct StandardOptions<'a, S: WithSane> {
+    device: &'a mut DeviceHandle<S>,
+}
+
+impl<S: WithSane> DeviceHandle<S> {
+    pub fn standard_options(&mut self) -> StandardOptions<S> {
+        StandardOptions { device: self }
+    }
+
+    /// Switches the device into or out of preview mode, see [`StandardOptions::set_preview`].
+    ///
+    /// # Errors
+    /// - [`Unsupported`][`crate::error::Status::Unsupported`]: The device does not have a preview mode.
+    pub fn set_preview(&mut self, preview: bool) -> Result<(), Error> {
+        self.standard_options().set_preview(preview).map(|_| ())
+    }
+
+    /// Sets the brightness, see [`StandardOptions::set_brightness`].
+    pub fn set_brightness(&mut self, value: i32) -> Result<Option<OwnedValue>, Error> {
+        self.standard_options().set_brightness(value)
+    }
+
+    /// Sets the contrast, see [`StandardOptions::set_contrast`].
+    pub fn set_contrast(&mut self, value: i32) -> Result<Option<OwnedValue>, Error> {
+        self.standard_options().set_contrast(value)
+    }
+
+    /// Lists the scan sources of the device, see [`StandardOptions::sources`].
+    pub fn sources(&mut self) -> Result<Vec<SaneString>, Error> {
+        self.standard_options().sources()
+    }
+
+    /// Selects the scan source, see [`StandardOptions::set_source`].
+    ///
+    /// # Errors
+    /// - [`Unsupported`][`crate::error::Status::Unsupported`]: The device does not support selecting a scan source.
+    /// - [`Inval`][`crate::error::Status::Inval`]: The source is not supported by the device.
+    pub fn set_source(&mut self, source: &SaneStr) -> Result<(), Error> {
+        self.standard_options().set_source(source).map(|_| ())
+    }
+
+    /// Sets the supported resolution closest to `dpi`, see
+    /// [`StandardOptions::set_resolution_nearest`].
+    pub fn set_resolution_nearest(&mut self, dpi: u32) -> Result<u32, Error> {
+        self.standard_options().set_resolution_nearest(dpi)
+    }
+
+    /// Lists the bit depths supported by the device, see [`StandardOptions::depths`].
+    pub fn depths(&mut self) -> Result<Vec<u32>, Error> {
+        self.standard_options().depths()
+    }
+
+    /// Sets the bit depth, see [`StandardOptions::set_depth`].
+    ///
+    /// # Errors
+    /// - [`Unsupported`][`crate::error::Status::Unsupported`]: The device does not support selecting a bit depth.
+    /// - [`Inval`][`crate::error::Status::Inval`]: The bit depth is not supported by the device.
+    pub fn set_depth(&mut self, bits: u32) -> Result<(), Error> {
+        self.standard_options().set_depth(bits).map(|_| ())
+    }
+}
+
+impl<S: WithSane> StandardOptions<'_, S> {
+    /// Gets the value of the option with the given name, or `None` if the device does not
+    /// have this option.
+    fn get(&mut self, name: &SaneStr) -> Result<Option<OwnedValue>, Error> {
+        match self.device.option_by_name(name) {
+            Some(mut option) => option.get(),
+            None => Ok(None),
+        }
+    }
+
+    /// Sets the value of the option with the given name.
+    ///
+    /// # Errors
+    /// - [`Unsupported`][`crate::error::Status::Unsupported`]: The device does not have this option.
+    /// - [`Inval`][`crate::error::Status::Inval`]: The option has a different type.
+    fn set(&mut self, name: &SaneStr, value: Value) -> Result<ControlInfo, Error> {
+        let Some(mut option) = self.device.option_by_name(name) else {
+            return Err(Error::from_sys_status(sys::Status::Unsupported));
+        };
+        if option.type_() != value.type_of() {
+            return Err(Error::from_sys_status(sys::Status::Inval));
+        }
+        option.set(value).map(|(info, _)| info)
+    }
+
+    /// Gets the current scan mode, or `None` if the device does not support selecting one.
+    pub fn get_mode(&mut self) -> Result<Option<ScanMode>, Error> {
+        match self.get(name::SCAN_MODE)? {
+            Some(OwnedValue::String(mode)) => Ok(Some(ScanMode::from(mode))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Selects the scan mode.
+    ///
+    /// # Errors
+    /// - [`Unsupported`][`crate::error::Status::Unsupported`]: The device does not support selecting a scan mode.
+    /// - [`Inval`][`crate::error::Status::Inval`]: The mode is not supported by the device.
+    pub fn set_mode(&mut self, mode: &ScanMode) -> Result<ControlInfo, Error> {
+        self.set(name::SCAN_MODE, Value::String(mode.as_sane_str()))
+    }
+
+    /// Gets whether preview mode is enabled, or `None` if the device does not have a
+    /// preview mode.
+    pub fn preview(&mut self) -> Result<Option<bool>, Error> {
+        match self.get(name::PREVIEW)? {
+            Some(OwnedValue::Bool(preview)) => Ok(Some(preview)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Enables or disables preview mode, in which the device scans quickly at a low
+    /// quality.
+    ///
+    /// # Errors
+    /// - [`Unsupported`][`crate::error::Status::Unsupported`]: The device does not have a preview mode.
+    pub fn set_preview(&mut self, preview: bool) -> Result<ControlInfo, Error> {
+        self.set(name::PREVIEW, Value::Bool(preview))
+    }
+
+    /// Lists the scan sources supported by the device, such as `Flatbed` or `ADF`. The list
+    /// is empty if the device does not support selecting a scan source.
+    pub fn sources(&mut self) -> Result<Vec<SaneString>, Error> {
+        let Some(option) = self.device.option_by_name(name::SCAN_SOURCE) else {
+            return Ok(Vec::new());
+        };
+        match option.constraint() {
+            Some(DeviceOptionConstraint::ListString(list)) => {
+                Ok(list.map(|source| source.to_owned()).collect())
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Whether the selected scan source is a document feeder, which is guessed from its
+    /// name, such as `ADF` or `Automatic Document Feeder`. Returns `false` if the device
+    /// does not support selecting a scan source.
+    pub(crate) fn source_is_feeder(&mut self) -> bool {
+        let Some(mut option) = self.device.option_by_name(name::SCAN_SOURCE) else {
+            return false;
+        };
+        let Ok(Some(OwnedValue::String(source))) = option.get() else {
+            return false;
+        };
+        is_feeder_name(source.borrow())
+    }
+
+    /// Selects the scan source, which must be one of [`Self::sources`].
+    ///
+    /// # Errors
+    /// - [`Unsupported`][`crate::error::Status::Unsupported`]: The device does not support selecting a scan source.
+    /// - [`Inval`][`crate::error::Status::Inval`]: The source is not supported by the device.
+    pub fn set_source(&mut self, source: &SaneStr) -> Result<ControlInfo, Error> {
+        self.set(name::SCAN_SOURCE, Value::String(source))
+    }
+
+    /// Lists the bit depths supported by the device, such as `1`, `8` or `16`. The list is
+    /// empty if the device does not support selecting a bit depth, or if it does not report
+    /// the supported depths as a list.
+    pub fn depths(&mut self) -> Result<Vec<u32>, Error> {
+        let Some(option) = self.device.option_by_name(name::BIT_DEPTH) else {
+            return Ok(Vec::new());
+        };
+        match option.constraint() {
+            Some(DeviceOptionConstraint::ListInt(list)) => Ok(list
+                .iter()
+                .filter_map(|bits| u32::try_from(*bits).ok())
+                .collect()),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Sets the bit depth, which must be permitted by the constraint of the option, see
+    /// [`Self::depths`].
+    ///
+    /// # Errors
+    /// - [`Unsupported`][`crate::error::Status::Unsupported`]: The device does not support selecting a bit depth.
+    /// - [`Inval`][`crate::error::Status::Inval`]: The bit depth is not supported by the device.
+    pub fn set_depth(&mut self, bits: u32) -> Result<ControlInfo, Error> {
+        let bits = i32::try_from(bits).map_err(|_| Error::from_sys_status(sys::Status::Inval))?;
+        if let Some(option) = self.device.option_by_name(name::BIT_DEPTH) {
+            if !option
+                .constraint()
+                .is_none_or(|constraint| depth_permitted(&constraint, bits))
+            {
+                return Err(Error::from_sys_status(sys::Status::Inval));
+            }
+        }
+        self.set(name::BIT_DEPTH, Value::Int(bits))
+    }
+
+    /// Sets the brightness, clamped to the range supported by the device. Returns the value
+    /// which has been set, or `None` if the device does not have this option.
+    ///
+    /// # Errors
+    /// - [`Inval`][`crate::error::Status::Inval`]: The option is not numeric.
+    pub fn set_brightness(&mut self, value: i32) -> Result<Option<OwnedValue>, Error> {
+        self.set_number_clamped(name::BRIGHTNESS, value)
+    }
+
+    /// Sets the contrast, clamped to the range supported by the device. Returns the value
+    /// which has been set, or `None` if the device does not have this option.
+    ///
+    /// # Errors
+    /// - [`Inval`][`crate::error::Status::Inval`]: The option is not numeric.
+    pub fn set_contrast(&mut self, value: i32) -> Result<Option<OwnedValue>, Error> {
+        self.set_number_clamped(name::CONTRAST, value)
+    }
+
+    /// Sets the resolution supported by the device which is closest to `dpi`, and returns
+    /// the resolution that has been set.
+    ///
+    /// # Errors
+    /// - [`Unsupported`][`crate::error::Status::Unsupported`]: The device does not support selecting a resolution.
+    /// - [`Inval`][`crate::error::Status::Inval`]: The option is not numeric.
+    pub fn set_resolution_nearest(&mut self, dpi: u32) -> Result<u32, Error> {
+        let Some(mut option) = self.device.option_by_name(name::RESOLUTION) else {
+            return Err(Error::from_sys_status(sys::Status::Unsupported));
+        };
+        let dpi = i32::try_from(dpi).unwrap_or(i32::MAX);
+        let value = match (option.type_(), option.constraint()) {
+            (ValueType::Int, Some(DeviceOptionConstraint::RangeInt { min, max, quant })) => {
+                Value::Int(nearest_in_range(dpi, min, max, quant))
+            }
+            (ValueType::Int, Some(DeviceOptionConstraint::ListInt(list))) => {
+                Value::Int(nearest_in_list(dpi, list.iter().copied()))
+            }
+            (ValueType::Int, _) => Value::Int(dpi),
+            (ValueType::Fixed, constraint) => {
+                let dpi = Fixed::new(dpi as f64).to_bits();
+                let bits = match constraint {
+                    Some(DeviceOptionConstraint::RangeFixed { min, max, quant }) => {
+                        nearest_in_range(dpi, min.to_bits(), max.to_bits(), quant.to_bits())
+                    }
+                    Some(DeviceOptionConstraint::ListFixed(list)) => {
+                        nearest_in_list(dpi, list.iter().map(|v| v.to_bits()))
+                    }
+                    _ => dpi,
+                };
+                Value::Fixed(Fixed::from_bits(bits))
+            }
+            _ => return Err(Error::from_sys_status(sys::Status::Inval)),
+        };
+        match option.set(value)? {
+            (_, OwnedValue::Int(dpi)) => Ok(dpi.max(0) as u32),
+            (_, OwnedValue::Fixed(dpi)) => Ok(f64::from(dpi).round().max(0.0) as u32),
+            _ => unreachable!("option type was checked above"),
+        }
+    }
+
+    /// Sets a numeric option, converting the value to the type of the option and clamping
+    /// it to its range. Returns `None` if the device does not have this option.
+    fn set_number_clamped(
+        &mut self,
+        name: &SaneStr,
+        value: i32,
+    ) -> Result<Option<OwnedValue>, Error> {
+        let Some(mut option) = self.device.option_by_name(name) else {
+            return Ok(None);
+        };
+        let value = match (option.type_(), option.constraint()) {
+            (ValueType::Int, Some(DeviceOptionConstraint::RangeInt { min, max, .. })) => {
+                Value::Int(value.clamp(min, max.max(min)))
+            }
+            (ValueType::Int, _) => Value::Int(value),
+            (ValueType::Fixed, Some(DeviceOptionConstraint::RangeFixed { min, max, .. })) => {
+                Value::Fixed(Fixed::new(value as f64).clamp(min, max.max(min)))
+            }
+            (ValueType::Fixed, _) => Value::Fixed(Fixed::new(value as f64)),
+            _ => return Err(Error::from_sys_status(sys::Status::Inval)),
+        };
+        option.set(value).map(|(_, value)| Some(value))
+    }
+}
+
+/// Whether the name of a scan source refers to a document feeder.
+fn is_feeder_name(source: &SaneStr) -> bool {
+    let source = source.to_bytes().to_ascii_lowercase();
+    [&b"adf"[..], b"feeder"]
+        .iter()
+        .any(|word| source.windows(word.len()).any(|window| window == *word))
+}
+
+/// Whether the integer `bits` is permitted by the constraint of the bit depth option.
+/// Ranges are checked without enumerating their values, which may be plentiful.
+fn depth_permitted(constraint: &DeviceOptionConstraint, bits: i32) -> bool {
+    match *constraint {
+        DeviceOptionConstraint::ListInt(list) => list.contains(&bits),
+        DeviceOptionConstraint::RangeInt { min, max, quant } => {
+            (min..=max).contains(&bits)
+                && (quant <= 0 || (bits as i64 - min as i64) % quant as i64 == 0)
+        }
+        _ => true,
+    }
+}
+
+/// Clamps `value` to the range and rounds it to the closest step of `quant` from `min`.
+fn nearest_in_range(value: i32, min: i32, max: i32, quant: i32) -> i32 {
+    let value = value.clamp(min, max.max(min)) as i64;
+    if quant <= 0 {
+        return value as i32;
+    }
+    let (min, quant) = (min as i64, quant as i64);
+    let steps = (value - min + quant / 2) / quant;
+    let snapped = min + steps * quant;
+    // Rounding up may exceed the maximum if it is not a step itself.
+    if snapped > max as i64 {
+        (snapped - quant) as i32
+    } else {
+        snapped as i32
+    }
+}
+
+/// Finds the value in `list` closest to `value`, or `value` itself if the list is empty.
+fn nearest_in_list(value: i32, list: impl Iterator<Item = i32>) -> i32 {
+    list.min_by_key(|item| (*item as i64 - value as i64).abs())
+        .unwrap_or(value)
+}
+
+/// Scan modes with a standardized name. Backend-specific modes are represented by
+/// [`ScanMode::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ScanMode {
+    Color,
+    Gray,
+    Lineart,
+    Halftone,
+    Other(SaneString),
+}
+
+impl ScanMode {
+    pub const COLOR: &'static SaneStr = SaneStr::from_cstr(c"Color");
+    pub const GRAY: &'static SaneStr = SaneStr::from_cstr(c"Gray");
+    pub const LINEART: &'static SaneStr = SaneStr::from_cstr(c"Lineart");
+    pub const HALFTONE: &'static SaneStr = SaneStr::from_cstr(c"Halftone");
+
+    pub fn as_sane_str(&self) -> &SaneStr {
+        match self {
+            Self::Color => Self::COLOR,
+            Self::Gray => Self::GRAY,
+            Self::Lineart => Self::LINEART,
+            Self::Halftone => Self::HALFTONE,
+            Self::Other(other) => other.borrow(),
+        }
+    }
+}
+
+impl From<&SaneStr> for ScanMode {
+    fn from(value: &SaneStr) -> Self {
+        match value {
+            v if v == Self::COLOR => Self::Color,
+            v if v == Self::GRAY => Self::Gray,
+            v if v == Self::LINEART => Self::Lineart,
+            v if v == Self::HALFTONE => Self::Halftone,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+}
+
+impl From<SaneString> for ScanMode {
+    fn from(value: SaneString) -> Self {
+        match Self::from(<SaneString as Borrow<SaneStr>>::borrow(&value)) {
+            Self::Other(_) => Self::Other(value),
+            known => known,
+        }
+    }
+}
+
+impl AsRef<SaneStr> for ScanMode {
+    fn as_ref(&self) -> &SaneStr {
+        self.as_sane_str()
+    }
+}
+
+impl fmt::Display for ScanMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_sane_str(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feeder_names() {
+        for name in [
+            c"ADF",
+            c"ADF Duplex",
+            c"Automatic Document Feeder",
+            c"adf front",
+        ] {
+            assert!(is_feeder_name(SaneStr::from_cstr(name)));
+        }
+        for name in [c"Flatbed", c"Transparency Adapter", c""] {
+            assert!(!is_feeder_name(SaneStr::from_cstr(name)));
+        }
+    }
+
+    #[test]
+    fn depth_permitted_by_list() {
+        let list = DeviceOptionConstraint::ListInt(&[1, 8, 16]);
+        assert!(depth_permitted(&list, 8));
+        assert!(!depth_permitted(&list, 12));
+    }
+
+    #[test]
+    fn depth_permitted_by_huge_range() {
+        let range = DeviceOptionConstraint::RangeInt {
+            min: 0,
+            max: i32::MAX,
+            quant: 8,
+        };
+        assert!(depth_permitted(&range, 16));
+        assert!(!depth_permitted(&range, 12));
+        assert!(!depth_permitted(&range, -8));
+        let continuous = DeviceOptionConstraint::RangeInt {
+            min: i32::MIN,
+            max: i32::MAX,
+            quant: 0,
+        };
+        assert!(depth_permitted(&continuous, 12));
+    }
+}
@@ -1,9 +1,17 @@
 pub mod frame_decoder;
 
-use core::fmt;
-use std::io;
+use core::{ffi::c_void, fmt, marker::PhantomData, ptr::NonNull};
+use std::{
+    borrow::BorrowMut,
+    io,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
 
-use crate::{error, proxied_sys::IoMode, sys, DeviceHandle, Error, WithSane};
+#[cfg(unix)]
+use std::os::fd::RawFd;
+
+use crate::{error, proxied_sys::IoMode, sys, DeviceHandle, Error, Sane, WithSane};
 
 pub use frame_decoder::{DecodedImage, DecodedImageFormat, FrameDecodeError, FrameDecoder};
 
@@ -11,43 +19,305 @@ impl<S: WithSane> DeviceHandle<S> {
     pub fn scan_blocking(self) -> ScanReader<S> {
         ScanReader::new(self)
     }
+
+    /// Scans an image and copies the raw data of its frames to `writer` without buffering
+    /// the entire image, for example to pipe it to another process or a network socket.
+    /// Returns the total number of bytes written.
+    ///
+    /// Multiple frames, such as the bands of a three-pass scanner, are concatenated. The
+    /// stream contains no header, so the caller must know the [parameters][`Self::get_parameters`]
+    /// of each frame to interpret it.
+    ///
+    /// # Errors
+    /// See [`Self::read`]. Errors of the writer are reported as
+    /// [`IoError`][`crate::error::Status::IoError`].
+    pub fn scan_to_writer(self, writer: &mut impl io::Write) -> Result<u64, Error> {
+        let mut reader = self.scan_blocking();
+        let mut written = 0;
+        let res = loop {
+            let mut frame = match reader.next_frame() {
+                Ok(Some(frame)) => frame,
+                Ok(None) => break Ok(written),
+                Err(err) => break Err(err),
+            };
+            match io::copy(&mut frame, writer) {
+                Ok(len) => written += len,
+                Err(err) => break Err(io_error_to_status(err)),
+            }
+        };
+        reader.cancel();
+        res
+    }
+
+    /// Low-level API to initiate the acquisition of a frame. Prefer using
+    /// [`Self::scan_blocking`] unless you need to implement a custom scan loop.
+    ///
+    /// After this call succeeded, the frame data can be read using [`Self::read`] until it
+    /// fails with [`Eof`][`crate::error::Status::Eof`]. If the
+    /// [parameters][`Self::get_parameters`] indicate that this was not the last frame of the
+    /// image, this function needs to be called again to acquire the next frame.
+    ///
+    /// # Errors
+    /// - [`Cancelled`][`crate::error::Status::Cancelled`]: The operation was cancelled through a call to [`Self::cancel`].
+    /// - [`DeviceBusy`][`crate::error::Status::DeviceBusy`]: The device is busy. The operation should be retried later.
+    /// - [`Jammed`][`crate::error::Status::Jammed`]: The document feeder is jammed.
+    /// - [`NoDocs`][`crate::error::Status::NoDocs`]: The document feeder is out of documents.
+    /// - [`CoverOpen`][`crate::error::Status::CoverOpen`]: The scanner cover is open.
+    /// - [`IoError`][`crate::error::Status::IoError`]: An error occurred while communicating with the device.
+    /// - [`NoMem`][`crate::error::Status::NoMem`]: An insufficient amount of memory is available.
+    /// - [`Inval`][`crate::error::Status::Inval`]: The scan cannot be started with the current set of options.
+    pub fn start(&mut self) -> Result<(), Error> {
+        let handle = self.inner.open_handle()?;
+        // SAFETY: handle is valid, library call is sequential (have access to Sane struct)
+        self.with_sane(|sane| unsafe { sane.sys_start(handle) })?;
+        self.scanning = true;
+        Ok(())
+    }
+
+    /// Low-level API to read data of the frame that is currently being acquired. The number
+    /// of bytes written to `buf` is returned. Blocks until at least one byte is available.
+    ///
+    /// Once the end of the frame is reached, this fails with
+    /// [`Eof`][`crate::error::Status::Eof`]. After this or any other error, [`Self::start`]
+    /// needs to be called before reading again.
+    ///
+    /// # Errors
+    /// - [`Inval`][`crate::error::Status::Inval`]: No frame is being acquired.
+    /// - [`Cancelled`][`crate::error::Status::Cancelled`]: The operation was cancelled through a call to [`Self::cancel`].
+    /// - [`Eof`][`crate::error::Status::Eof`]: No more data is available for the current frame.
+    /// - [`Jammed`][`crate::error::Status::Jammed`]: The document feeder is jammed.
+    /// - [`NoDocs`][`crate::error::Status::NoDocs`]: The document feeder is out of documents.
+    /// - [`CoverOpen`][`crate::error::Status::CoverOpen`]: The scanner cover is open.
+    /// - [`IoError`][`crate::error::Status::IoError`]: An error occurred while communicating with the device.
+    /// - [`NoMem`][`crate::error::Status::NoMem`]: An insufficient amount of memory is available.
+    /// - [`AccessDenied`][`crate::error::Status::AccessDenied`]: Access to the device has been denied due to insufficient or invalid authentication.
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if !self.scanning {
+            return Err(Error::from_sys_status(sys::Status::Inval));
+        }
+        let handle = self.inner.handle;
+        // SAFETY: handle is valid, device is scanning, call is sequential
+        let res = self.with_sane(|sane| unsafe { sane.sys_read(handle, buf) });
+        if res.is_err() {
+            self.scanning = false;
+        }
+        res
+    }
+
+    /// Low-level API to cancel the currently pending operation of the device as
+    /// quickly as possible.
+    pub fn cancel(&mut self) {
+        self.inner.cancel();
+        self.scanning = false;
+    }
+
+    /// Creates a handle which can be used to cancel the currently pending operation of
+    /// this device from another thread or from within a signal handler, for example
+    /// when the user presses Ctrl-C during a scan.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        CancelHandle {
+            handle: self.inner.handle,
+        }
+    }
+
+    /// Scans an entire image and returns the data of each frame. If this fails with an
+    /// [`IoError`][`crate::error::Status::IoError`], the device is
+    /// [reconnected][`Self::reconnect`] and the scan is retried once.
+    ///
+    /// As reconnecting resets all options, `restore_options` is called after
+    /// reconnecting to set them again before retrying.
+    pub fn scan_image_resilient(
+        &mut self,
+        restore_options: impl FnOnce(&mut Self) -> Result<(), Error>,
+    ) -> Result<Vec<(FrameParameters, Vec<u8>)>, Error> {
+        match self.scan_image_frames() {
+            Err(err) if err.status() == error::Status::IoError => {
+                self.reconnect()?;
+                restore_options(self)?;
+                self.scan_image_frames()
+            }
+            res => res,
+        }
+    }
+
+    fn scan_image_frames(&mut self) -> Result<Vec<(FrameParameters, Vec<u8>)>, Error> {
+        let mut reader = ScanReader::<S, &mut Self>::new(self);
+        let res = reader.read_image_frames();
+        reader.cancel();
+        res
+    }
 }
 
-pub struct ScanReader<S: WithSane> {
-    device: DeviceHandle<S>,
+/// Cancels the pending operation of a device independently of its [`DeviceHandle`].
+/// See [`DeviceHandle::cancel_handle`].
+#[derive(Debug, Clone, Copy)]
+pub struct CancelHandle {
+    handle: NonNull<c_void>,
+}
+
+impl CancelHandle {
+    /// Initiates the cancellation of the currently pending operation of the device. The
+    /// cancelled call returns with [`Cancelled`][`crate::error::Status::Cancelled`]
+    /// once cancellation has completed.
+    ///
+    /// # Safety
+    /// The [`DeviceHandle`] this was created from must not have been dropped, and must not
+    /// have been [reconnected][`DeviceHandle::reconnect`] since, as the underlying handle
+    /// would no longer be valid.
+    pub unsafe fn cancel(&self) {
+        // SAFETY: The handle is valid as guaranteed by the caller. This call may be made
+        // asynchronously by specification.
+        unsafe { Sane::<()>::sys_cancel(self.handle) }
+    }
+}
+
+// SAFETY: `sane_cancel` is the only call made through the handle, and it may be made from
+// any thread or signal handler by specification.
+unsafe impl Send for CancelHandle {}
+
+// SAFETY: See above.
+unsafe impl Sync for CancelHandle {}
+
+/// Reads the frames of a scan. The device is usually owned by the reader, see
+/// [`DeviceHandle::scan_blocking`], but it may also be borrowed mutably (`D` being
+/// `&mut DeviceHandle<S>`).
+pub struct ScanReader<S: WithSane, D: BorrowMut<DeviceHandle<S>> = DeviceHandle<S>> {
+    device: D,
+    _phant: PhantomData<fn() -> S>,
     done: bool,
+    /// Whether the acquisition of the next frame was already started by
+    /// [`ScanReader::more_pages`].
+    prestarted: bool,
+    frames_read: u32,
+    images_read: u32,
 }
 
-impl<S: WithSane> ScanReader<S> {
-    fn new(device: DeviceHandle<S>) -> Self {
+impl<S: WithSane, D: BorrowMut<DeviceHandle<S>>> ScanReader<S, D> {
+    fn new(device: D) -> Self {
         Self {
             device,
+            _phant: PhantomData,
             done: false,
+            prestarted: false,
+            frames_read: 0,
+            images_read: 0,
         }
     }
 
-    pub fn into_inner(mut self) -> DeviceHandle<S> {
+    pub fn into_inner(mut self) -> D {
         self.cancel();
         self.device
     }
 
     pub fn device(&self) -> &DeviceHandle<S> {
-        &self.device
+        self.device.borrow()
     }
 
     pub fn cancel(&mut self) {
-        self.device.inner.cancel();
+        self.device().inner.cancel();
         self.done = true;
+        self.prestarted = false;
+    }
+
+    /// Checks whether another page is available, for example in an automatic document
+    /// feeder, which allows driving a batch scan without treating
+    /// [`NoDocs`][`crate::error::Status::NoDocs`] as an error:
+    ///
+    /// ```no_run
+    /// # use libsane::scan::MorePages;
+    /// # fn scan(mut reader: libsane::scan::ScanReader<&libsane::Sane<libsane::NoAuth>>) -> Result<(), libsane::Error> {
+    /// loop {
+    ///     let more = reader.more_pages()?;
+    ///     if more == MorePages::No {
+    ///         break;
+    ///     }
+    ///     while let Some(mut frame) = reader.next_frame()? {
+    ///         let mut data = Vec::new();
+    ///         frame.read_full_frame(&mut data)?;
+    ///     }
+    ///     // For example a flatbed scanner, which never runs out of pages
+    ///     if more == MorePages::Unknown {
+    ///         break;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// SANE provides no way to query this without side effects, so the acquisition of
+    /// the next frame is started, which is then continued by [`Self::next_frame`]. Whether
+    /// the backend reports a missing page when starting is up to its implementation, so
+    /// this is only a heuristic. Some backends start feeding the page right away.
+    ///
+    /// Flatbed scanners always report another page, so [`MorePages::Yes`] is only returned
+    /// if the selected scan source is a document feeder, which is guessed from its name.
+    /// Otherwise, [`MorePages::Unknown`] is returned.
+    ///
+    /// # Errors
+    /// See [`DeviceHandle::start`], except that [`NoDocs`][`crate::error::Status::NoDocs`]
+    /// and [`Eof`][`crate::error::Status::Eof`] result in [`MorePages::No`].
+    pub fn more_pages(&mut self) -> Result<MorePages, Error> {
+        let more = if self
+            .device
+            .borrow_mut()
+            .standard_options()
+            .source_is_feeder()
+        {
+            MorePages::Yes
+        } else {
+            MorePages::Unknown
+        };
+        if self.prestarted {
+            return Ok(more);
+        }
+        let handle = self.device().inner.open_handle()?;
+        let res = self
+            .device()
+            // SAFETY: handle is valid, library call is sequential (have access to Sane struct)
+            .with_sane(|sane| unsafe { sane.sys_start(handle) });
+        match res {
+            Ok(()) => {
+                self.prestarted = true;
+                self.done = false;
+                Ok(more)
+            }
+            Err(err) if matches!(err.sys_status(), sys::Status::NoDocs | sys::Status::Eof) => {
+                self.done = true;
+                Ok(MorePages::No)
+            }
+            Err(err) => Err(err),
+        }
     }
 
-    pub fn next_frame(&mut self) -> Result<Option<FrameReader<S>>, Error> {
+    /// Number of frames that have been read completely.
+    pub fn frames_read(&self) -> u32 {
+        self.frames_read
+    }
+
+    /// Number of images that have been read completely, which is incremented once the
+    /// last frame of an image is complete. During a batch scan, this is the number of
+    /// pages that have been scanned so far.
+    pub fn images_read(&self) -> u32 {
+        self.images_read
+    }
+
+    /// Starts the acquisition of the next frame, or returns `None` if the image is complete.
+    ///
+    /// Three-pass scanners deliver the red, green and blue bands as separate frames. If the
+    /// number of lines is not known in advance, the height of each band is only known once
+    /// it has been read completely and the bands may disagree, which is reported by
+    /// [`FrameDecoder::write`] as [`FrameDecodeError::SubFrameHeightMismatch`].
+    pub fn next_frame(&mut self) -> Result<Option<FrameReader<S, D>>, Error> {
         if self.done {
             return Ok(None);
         };
-        let params = self.device.with_sane(|sane| {
-            let handle = self.device.inner.handle;
-            // SAFETY: handle is valid, library call is sequential (have access to Sane struct)
-            unsafe { sane.sys_start(handle)? };
+        let prestarted = std::mem::take(&mut self.prestarted);
+        let handle = self.device().inner.open_handle()?;
+        let params = self.device().with_sane(|sane| {
+            if !prestarted {
+                // SAFETY: handle is valid, library call is sequential (have access to Sane struct)
+                unsafe { sane.sys_start(handle)? };
+            }
             // SAFETY: see above, and start has been called
             let res = unsafe { sane.sys_set_io_mode(handle, IoMode::Blocking) };
             // Blocking is always supported, but the backend might always return an error.
@@ -60,22 +330,214 @@ impl<S: WithSane> ScanReader<S> {
             // SAFETY: handle is valid, and call is sequential
             unsafe { sane.sys_get_parameters(handle) }
         })?;
+        self.device().inner.parameters_read();
         Ok(Some(FrameReader::new(self, params.into())))
     }
+
+    /// Reads all remaining frames of the current image, each into its own buffer along
+    /// with its parameters. Unlike [`FrameReader::read_full_frame`], this keeps the red,
+    /// green and blue bands of three-pass scanners apart, so that they can be inspected
+    /// individually or passed to a [`FrameDecoder`].
+    ///
+    /// Returns an empty list if the image is already complete.
+    pub fn read_image_frames(&mut self) -> Result<Vec<(FrameParameters, Vec<u8>)>, Error> {
+        let mut frames = Vec::new();
+        while let Some(mut frame_reader) = self.next_frame()? {
+            let mut data = Vec::new();
+            frame_reader.read_full_frame(&mut data)?;
+            frames.push((*frame_reader.parameters(), data));
+        }
+        Ok(frames)
+    }
+}
+
+impl<S: WithSane> ScanReader<S> {
+    /// Returns an iterator which scans and decodes one image after the other, for example
+    /// one per page of an automatic document feeder. The frames of each image are
+    /// assembled using a [`FrameDecoder`] with the default configuration.
+    ///
+    /// The iterator ends once the device reports [`NoDocs`][`crate::error::Status::NoDocs`]
+    /// or [`Eof`][`crate::error::Status::Eof`] when starting the next image, or after the
+    /// first error. Flatbed scanners never run out of documents, so only the first image
+    /// should be taken in this case.
+    pub fn images(self) -> ScanImages<S> {
+        self.images_with(FrameDecoder::builder())
+    }
+
+    /// Like [`Self::images`], but every image is decoded by a [`FrameDecoder`] built from
+    /// `decoder`.
+    pub fn images_with(self, decoder: frame_decoder::Builder) -> ScanImages<S> {
+        ScanImages {
+            reader: self,
+            decoder,
+            buffer: Vec::new(),
+            finished: false,
+        }
+    }
+}
+
+impl<S: WithSane, D: BorrowMut<DeviceHandle<S>>> fmt::Debug for ScanReader<S, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(stringify!(ScanReader))
+            .field("device", self.device())
+            .field("done", &self.done)
+            .field("prestarted", &self.prestarted)
+            .field("frames_read", &self.frames_read)
+            .field("images_read", &self.images_read)
+            .finish()
+    }
+}
+
+/// Iterator over the decoded images of a scan, see [`ScanReader::images`].
+pub struct ScanImages<S: WithSane> {
+    reader: ScanReader<S>,
+    decoder: frame_decoder::Builder,
+    /// Reused for the data of every frame.
+    buffer: Vec<u8>,
+    finished: bool,
+}
+
+impl<S: WithSane> ScanImages<S> {
+    pub fn into_inner(self) -> DeviceHandle<S> {
+        self.reader.into_inner()
+    }
+
+    pub fn device(&self) -> &DeviceHandle<S> {
+        self.reader.device()
+    }
+
+    /// Reads all frames of the next image, or returns `None` if there are no more images.
+    fn read_image(&mut self) -> Result<Option<DecodedImage>, ScanImageError> {
+        // The previous image is complete, the next one is started by the next frame.
+        self.reader.done = false;
+        let mut decoder = self.decoder.clone().build();
+        let mut first = true;
+        loop {
+            let mut frame_reader = match self.reader.next_frame() {
+                Ok(Some(frame_reader)) => frame_reader,
+                Ok(None) => break,
+                Err(err)
+                    if first
+                        && matches!(err.sys_status(), sys::Status::NoDocs | sys::Status::Eof) =>
+                {
+                    return Ok(None);
+                }
+                Err(err) => return Err(err.into()),
+            };
+            first = false;
+            decoder.reserve_for(frame_reader.parameters());
+            frame_reader.read_full_frame_into(&mut self.buffer)?;
+            decoder.write(&self.buffer, frame_reader.parameters())?;
+        }
+        decoder
+            .into_image()
+            .map(Some)
+            .map_err(|_| ScanImageError::Incomplete)
+    }
+}
+
+impl<S: WithSane> Iterator for ScanImages<S> {
+    type Item = Result<DecodedImage, ScanImageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let res = self.read_image().transpose();
+        if !matches!(res, Some(Ok(_))) {
+            self.finished = true;
+            self.reader.cancel();
+        }
+        res
+    }
+}
+
+impl<S: WithSane> std::iter::FusedIterator for ScanImages<S> {}
+
+impl<S: WithSane> fmt::Debug for ScanImages<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(stringify!(ScanImages))
+            .field("reader", &self.reader)
+            .field("decoder", &self.decoder)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Error returned by [`ScanImages`].
+/// Whether another page is available, see [`ScanReader::more_pages`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MorePages {
+    /// The acquisition of another page from a document feeder has started.
+    Yes,
+    /// The backend reported that no page is left.
+    No,
+    /// The acquisition of another page has started, but the scan source is not known to
+    /// be a document feeder. It may be a flatbed scanner, which never runs out of pages.
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanImageError {
+    /// Reading from the device failed.
+    Scan(Error),
+    /// The frame data could not be decoded.
+    Decode(FrameDecodeError),
+    /// The device reported the last frame before all frames of the image were received,
+    /// for example if a band of a three-pass scan is missing.
+    Incomplete,
+}
+
+impl fmt::Display for ScanImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scan(err) => fmt::Display::fmt(err, f),
+            Self::Decode(err) => fmt::Display::fmt(err, f),
+            Self::Incomplete => f.write_str("the image is incomplete"),
+        }
+    }
+}
+
+impl std::error::Error for ScanImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Scan(err) => Some(err),
+            Self::Decode(err) => Some(err),
+            Self::Incomplete => None,
+        }
+    }
+}
+
+impl From<Error> for ScanImageError {
+    fn from(value: Error) -> Self {
+        Self::Scan(value)
+    }
 }
 
-pub struct FrameReader<'a, S: WithSane> {
-    scanner: &'a mut ScanReader<S>,
+impl From<FrameDecodeError> for ScanImageError {
+    fn from(value: FrameDecodeError) -> Self {
+        Self::Decode(value)
+    }
+}
+
+pub struct FrameReader<'a, S: WithSane, D: BorrowMut<DeviceHandle<S>> = DeviceHandle<S>> {
+    scanner: &'a mut ScanReader<S, D>,
     params: FrameParameters,
     started: bool,
+    /// Whether the frame has been read completely and was counted by the scanner.
+    completed: bool,
+    /// Number of bytes read from this frame so far.
+    bytes_read: u64,
 }
 
-impl<'a, S: WithSane> FrameReader<'a, S> {
-    fn new(scanner: &'a mut ScanReader<S>, params: FrameParameters) -> Self {
+impl<'a, S: WithSane, D: BorrowMut<DeviceHandle<S>>> FrameReader<'a, S, D> {
+    fn new(scanner: &'a mut ScanReader<S, D>, params: FrameParameters) -> Self {
         Self {
             scanner,
             params,
             started: false,
+            completed: false,
+            bytes_read: 0,
         }
     }
 
@@ -83,34 +545,229 @@ impl<'a, S: WithSane> FrameReader<'a, S> {
         &self.params
     }
 
+    /// Counts this frame as read completely.
+    fn complete(&mut self) {
+        if !self.completed {
+            self.completed = true;
+            self.scanner.frames_read += 1;
+            if self.params.last_frame {
+                self.scanner.images_read += 1;
+            }
+        }
+    }
+
+    /// Number of bytes of this frame that have been read so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// Number of lines of this frame that have not been read completely yet, if the
+    /// total number of lines is known.
+    pub fn lines_remaining(&self) -> Option<u32> {
+        let lines = self.params.lines?;
+        let lines_read = self
+            .bytes_read
+            .checked_div(self.params.bytes_per_line as u64)
+            .unwrap_or(0);
+        Some(lines.saturating_sub(lines_read.try_into().unwrap_or(u32::MAX)))
+    }
+
+    /// Queries the parameters of this frame again. Some backends only know the
+    /// number of lines after the acquisition has begun.
+    pub fn refresh_parameters(&mut self) -> Result<&FrameParameters, Error> {
+        self.params = self.scanner.device().inner.get_parameters()?;
+        Ok(&self.params)
+    }
+
     pub fn read_frame(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
         let last_frame = self.params.last_frame;
-        self.scanner.device.with_sane(|sane| {
+        let handle = self.scanner.device().inner.handle;
+        let res = self.scanner.device.borrow().with_sane(|sane| {
             self.started = true;
             // SAFETY: handle is valid, device is scanning, call is sequential
-            let res = unsafe { sane.sys_read(self.scanner.device.inner.handle, buf) };
-            if let Err(err) = &res {
-                if matches!(err.sys_status(), sys::Status::Cancelled | sys::Status::Eof if last_frame) {
-                    self.scanner.done = true;
+            let res = unsafe { sane.sys_read(handle, buf) };
+            match &res {
+                Ok(len) => self.bytes_read += *len as u64,
+                Err(err) => {
+                    if matches!(err.sys_status(), sys::Status::Cancelled | sys::Status::Eof if last_frame) {
+                        self.scanner.done = true;
+                    }
                 }
             }
             res
-        })
+        });
+        if res.is_err_and(|err| err.sys_status() == sys::Status::Eof) {
+            self.complete();
+        }
+        res
+    }
+
+    /// Obtains the select file-descriptor of the backend, which becomes readable once
+    /// data of this frame is available.
+    ///
+    /// The descriptor is only valid while this frame is being read, i.e. until reading
+    /// fails with [`Eof`][`crate::error::Status::Eof`] or the scan is cancelled.
+    ///
+    /// # Errors
+    /// - [`Unsupported`][`crate::error::Status::Unsupported`]: The backend does not provide a select file-descriptor.
+    #[cfg(unix)]
+    pub fn select_fd(&self) -> Result<SelectFd, Error> {
+        let handle = self.scanner.device().inner.handle;
+        self.scanner
+            .device()
+            // SAFETY: handle is valid, and call is sequential. SelectFd only allows polling
+            // the descriptor.
+            .with_sane(|sane| unsafe { sane.sys_get_select_fd(handle) })
+            .map(SelectFd)
+    }
+
+    /// Returns whether the next call to [`Self::read_frame`] returns immediately, without
+    /// blocking. This polls the [select file-descriptor][`Self::select_fd`] without waiting,
+    /// which allows an event loop to only read once data is available.
+    ///
+    /// This also returns `true` at the end of the frame, when the next read reports
+    /// [`Eof`][`crate::error::Status::Eof`].
+    ///
+    /// # Errors
+    /// - [`Unsupported`][`crate::error::Status::Unsupported`]: The backend does not provide a
+    ///   select file-descriptor, or the platform is not Unix.
+    /// - [`IoError`][`crate::error::Status::IoError`]: Polling the descriptor failed.
+    pub fn data_ready(&self) -> Result<bool, Error> {
+        #[cfg(unix)]
+        {
+            match self.select_fd()?.is_readable(Some(Duration::ZERO)) {
+                Ok(ready) => Ok(ready),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => Ok(false),
+                Err(_) => Err(Error::from_sys_status(sys::Status::IoError)),
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            Err(Error::from_sys_status(sys::Status::Unsupported))
+        }
+    }
+
+    /// Sets whether [`Self::read_frame`] returns immediately with zero bytes read if no data
+    /// is available, instead of blocking. The I/O mode is reset to blocking when the next
+    /// frame is started.
+    ///
+    /// Note that the [`io::Read`] implementation treats reading zero bytes as the end of
+    /// the frame, so it should only be used in blocking mode.
+    ///
+    /// # Errors
+    /// - [`Unsupported`][`crate::error::Status::Unsupported`]: The backend does not support
+    ///   non-blocking I/O.
+    /// - [`Inval`][`crate::error::Status::Inval`]: The acquisition of this frame has ended.
+    pub fn set_non_blocking(&mut self, non_blocking: bool) -> Result<(), Error> {
+        let handle = self.scanner.device().inner.handle;
+        let mode = match non_blocking {
+            false => IoMode::Blocking,
+            true => IoMode::NonBlocking,
+        };
+        self.scanner
+            .device()
+            // SAFETY: handle is valid, start has been called, and call is sequential
+            .with_sane(|sane| unsafe { sane.sys_set_io_mode(handle, mode) })
+    }
+
+    /// Like [`Self::read_frame`], but waits at most `timeout` for data to become available
+    /// using the [select file-descriptor][`Self::select_fd`] of the backend. Returns `None`
+    /// if no data was available in time, which prevents a hung backend from blocking the
+    /// thread forever.
+    ///
+    /// If the backend does not provide a select file-descriptor, or on platforms other than
+    /// Unix, this falls back to a blocking read and the timeout has no effect.
+    pub fn read_frame_timeout(
+        &mut self,
+        buf: &mut [u8],
+        timeout: Duration,
+    ) -> Result<Option<usize>, Error> {
+        #[cfg(unix)]
+        if let Ok(fd) = self.select_fd() {
+            match fd.is_readable(Some(timeout)) {
+                Ok(true) => {}
+                Ok(false) => return Ok(None),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => return Ok(None),
+                // Let the read report the actual problem.
+                Err(_) => {}
+            }
+            // The descriptor is readable, but reading without blocking guards against
+            // backends that signal readiness too early.
+            let non_blocking = self.set_non_blocking(true).is_ok();
+            let res = self.read_frame(buf);
+            if non_blocking {
+                // Blocking mode is restored on every path. Once the frame has ended, the
+                // mode cannot be changed anymore, so only a successful read reports it.
+                let restored = self.set_non_blocking(false);
+                if res.is_ok() {
+                    restored?;
+                }
+            }
+            return res.map(|len| (len != 0).then_some(len));
+        }
+        #[cfg(not(unix))]
+        let _ = timeout;
+        self.read_frame(buf).map(Some)
     }
 
+    /// Like [`Self::read_frame`], but waits for data to become available using the
+    /// select file-descriptor of the backend while periodically checking `cancel`. Once
+    /// `cancel` is set, the acquisition is cancelled and this fails with
+    /// [`Cancelled`][`crate::error::Status::Cancelled`].
+    ///
+    /// If the backend does not provide a select file-descriptor, or on platforms other than
+    /// Unix, this falls back to a blocking read, and `cancel` is only checked before reading.
+    pub fn read_frame_cancellable(
+        &mut self,
+        buf: &mut [u8],
+        cancel: &AtomicBool,
+    ) -> Result<usize, Error> {
+        let handle = self.scanner.device().inner.handle;
+        let select_fd = self
+            .scanner
+            .device()
+            // SAFETY: handle is valid, and call is sequential. The descriptor is only polled.
+            .with_sane(|sane| unsafe { sane.sys_get_select_fd(handle) });
+        #[cfg(not(unix))]
+        if let Ok(fd) = select_fd {
+            match fd {}
+        }
+        #[cfg(unix)]
+        if let Ok(fd) = select_fd.map(SelectFd) {
+            while !cancel.load(Ordering::Relaxed) {
+                match fd.is_readable(Some(CANCEL_POLL_INTERVAL)) {
+                    Ok(true) => break,
+                    Ok(false) => {}
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                    // Let the blocking read report the actual problem.
+                    Err(_) => break,
+                }
+            }
+        }
+        if cancel.load(Ordering::Relaxed) {
+            self.scanner.device().inner.cancel();
+        }
+        self.read_frame(buf)
+    }
+
+    /// Reads the entire frame and appends it to `buf_vec`, which allows concatenating
+    /// multiple frames. Use [`Self::read_full_frame_into`] to reuse a buffer instead.
+    ///
+    /// # Panics
+    /// If a part of this frame has already been read.
     pub fn read_full_frame(&mut self, buf_vec: &mut Vec<u8>) -> Result<(), Error> {
         assert!(
             !self.started,
             "attempt to read entire frame after partial read"
         );
-        self.scanner.device.with_sane(|sane| {
+        let handle = self.scanner.device().inner.handle;
+        let res = self.scanner.device.borrow().with_sane(|sane| {
             self.started = true;
             if self.params.last_frame {
                 self.scanner.done = true;
             }
             let bytes_per_line = self.params.bytes_per_line;
             let lines = self.params.lines;
-            let handle = self.scanner.device.inner.handle;
             if let Some(lines) = lines {
                 let bytes_to_read = (bytes_per_line * lines) as usize;
                 buf_vec.reserve_exact(bytes_to_read);
@@ -125,6 +782,7 @@ impl<'a, S: WithSane> FrameReader<'a, S> {
                         Err(err) => return Err(err),
                         Ok(read_len) => {
                             debug_assert_ne!(read_len, 0);
+                            self.bytes_read += read_len as u64;
                             buf = &mut buf[read_len..];
                         }
                     };
@@ -150,6 +808,7 @@ impl<'a, S: WithSane> FrameReader<'a, S> {
                         Err(err) => return Err(err),
                         Ok(read_len) => {
                             debug_assert_ne!(read_len, 0);
+                            self.bytes_read += read_len as u64;
                             // SAFETY: read_len bytes were initialized
                             unsafe { buf_vec.set_len(buf_vec.len() + read_len) }
                             if read_len < reserved_bytes / 2 {
@@ -162,11 +821,26 @@ impl<'a, S: WithSane> FrameReader<'a, S> {
                 }
                 Ok(())
             }
-        })
+        });
+        if res.is_ok() {
+            self.complete();
+        }
+        res
+    }
+
+    /// Like [`Self::read_full_frame`], but clears `buf` first, so that it only contains
+    /// this frame. The capacity of `buf` is kept, which is useful to reuse a buffer for
+    /// every page of a batch scan.
+    ///
+    /// # Panics
+    /// If a part of this frame has already been read.
+    pub fn read_full_frame_into(&mut self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        buf.clear();
+        self.read_full_frame(buf)
     }
 }
 
-impl<S: WithSane> io::Read for FrameReader<'_, S> {
+impl<S: WithSane, D: BorrowMut<DeviceHandle<S>>> io::Read for FrameReader<'_, S, D> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         match self.read_frame(buf) {
             Ok(len) => Ok(len),
@@ -184,6 +858,142 @@ impl<S: WithSane> io::Read for FrameReader<'_, S> {
     }
 }
 
+/// How often [`FrameReader::read_frame_cancellable`] checks for cancellation.
+#[cfg(unix)]
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// File-descriptor of the backend which is readable if and only if image data is
+/// available. See [`FrameReader::select_fd`].
+///
+/// The only operation permitted on this descriptor is a test whether it is readable, any
+/// other operation makes the behavior of the backend unpredictable. It is owned and closed
+/// by the backend.
+#[cfg(unix)]
+#[derive(Debug, Clone, Copy)]
+pub struct SelectFd(RawFd);
+
+#[cfg(unix)]
+impl SelectFd {
+    /// Waits until image data is available or the timeout elapses, and returns whether data
+    /// is available. Without a timeout, this waits indefinitely.
+    ///
+    /// This also returns `true` once the backend has closed the descriptor at the end of
+    /// the frame, in which case the next read reports [`Eof`][`crate::error::Status::Eof`].
+    pub fn is_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        let mut pollfd = libc::pollfd {
+            fd: self.0,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: pollfd is a valid array of length 1.
+        match unsafe { libc::poll(&mut pollfd, 1, poll_timeout(timeout)) } {
+            -1 => Err(io::Error::last_os_error()),
+            0 => Ok(false),
+            _ => Ok(true),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl std::os::fd::AsRawFd for SelectFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+/// Converts a timeout to milliseconds for `poll`, where `-1` waits indefinitely.
+#[cfg(unix)]
+fn poll_timeout(timeout: Option<Duration>) -> libc::c_int {
+    match timeout {
+        Some(timeout) => timeout.as_millis().min(libc::c_int::MAX as u128) as libc::c_int,
+        None => -1,
+    }
+}
+
+/// A set of [select file-descriptors][`SelectFd`], which allows a single thread to wait
+/// for data of multiple scans in progress, for example of several scanners.
+///
+/// Once a descriptor is readable, the data still needs to be read using
+/// [`FrameReader::read_frame`] of the respective frame.
+#[cfg(unix)]
+#[derive(Debug, Clone, Default)]
+pub struct SelectSet {
+    fds: Vec<SelectFd>,
+}
+
+#[cfg(unix)]
+impl SelectSet {
+    pub const fn new() -> Self {
+        Self { fds: Vec::new() }
+    }
+
+    /// Adds a descriptor to this set and returns its index, which is used by
+    /// [`Self::poll_any`] to report it as readable.
+    pub fn push(&mut self, fd: SelectFd) -> usize {
+        self.fds.push(fd);
+        self.fds.len() - 1
+    }
+
+    /// Removes all descriptors, for example once the frames they belong to are complete.
+    pub fn clear(&mut self) {
+        self.fds.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.fds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fds.is_empty()
+    }
+
+    /// Waits until any of the descriptors is readable or the timeout elapses, and returns
+    /// the indices of all readable descriptors. The list is empty if the timeout elapsed.
+    /// Without a timeout, this waits indefinitely.
+    ///
+    /// Like [`SelectFd::is_readable`], a descriptor is also reported once the backend has
+    /// closed it at the end of the frame.
+    pub fn poll_any(&self, timeout: Option<Duration>) -> io::Result<Vec<usize>> {
+        let mut pollfds: Vec<_> = self
+            .fds
+            .iter()
+            .map(|fd| libc::pollfd {
+                fd: fd.0,
+                events: libc::POLLIN,
+                revents: 0,
+            })
+            .collect();
+        let nfds = libc::nfds_t::try_from(pollfds.len())
+            .map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+        // SAFETY: pollfds is a valid array of length nfds.
+        if unsafe { libc::poll(pollfds.as_mut_ptr(), nfds, poll_timeout(timeout)) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(pollfds
+            .iter()
+            .enumerate()
+            .filter(|(_, pollfd)| pollfd.revents != 0)
+            .map(|(index, _)| index)
+            .collect())
+    }
+}
+
+#[cfg(unix)]
+impl FromIterator<SelectFd> for SelectSet {
+    fn from_iter<T: IntoIterator<Item = SelectFd>>(iter: T) -> Self {
+        Self {
+            fds: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Extend<SelectFd> for SelectSet {
+    fn extend<T: IntoIterator<Item = SelectFd>>(&mut self, iter: T) {
+        self.fds.extend(iter);
+    }
+}
+
 fn read_error_to_io(error: Error) -> io::Error {
     let kind = match error.status() {
         error::Status::Cancelled => io::ErrorKind::BrokenPipe,
@@ -197,6 +1007,16 @@ fn read_error_to_io(error: Error) -> io::Error {
     io::Error::new(kind, error)
 }
 
+/// Recovers the status of an error created by [`read_error_to_io`]. Other errors, such
+/// as those of a writer, are reported as [`IoError`][`crate::error::Status::IoError`].
+fn io_error_to_status(error: io::Error) -> Error {
+    error
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<Error>())
+        .copied()
+        .unwrap_or(Error::from_sys_status(sys::Status::IoError))
+}
+
 #[derive(Clone, Copy)]
 pub struct FrameParameters {
     format: sys::Frame,
@@ -213,10 +1033,38 @@ pub struct FrameParameters {
 }
 
 impl FrameParameters {
+    /// Creates frame parameters, for example to feed a [`FrameDecoder`] with data that
+    /// was not acquired from a device.
+    ///
+    /// # Panics
+    /// If `format` is [`FrameFormat::Unsupported`].
+    pub const fn new(
+        format: FrameFormat,
+        last_frame: bool,
+        bytes_per_line: u32,
+        pixels_per_line: u32,
+        lines: Option<u32>,
+        depth: u32,
+    ) -> Self {
+        let Some(format) = format.to_sys() else {
+            panic!("cannot create frame parameters with an unsupported format");
+        };
+        Self {
+            format,
+            last_frame,
+            bytes_per_line,
+            pixels_per_line,
+            lines,
+            depth,
+        }
+    }
+
     pub fn format(&self) -> FrameFormat {
         self.format.into()
     }
 
+    /// Raw format of the frame as reported by the backend, which may not be known to
+    /// [`FrameFormat`].
     pub fn sys_format(&self) -> sys::Frame {
         self.format
     }
@@ -278,6 +1126,19 @@ impl FrameFormat {
     pub const fn is_rgb(&self) -> bool {
         matches!(self, Self::Rgb | Self::Red | Self::Green | Self::Blue)
     }
+
+    /// Converts this format back into its SANE representation, unless it is
+    /// [`Unsupported`][`Self::Unsupported`].
+    pub const fn to_sys(&self) -> Option<sys::Frame> {
+        match self {
+            Self::Gray => Some(sys::Frame::Gray),
+            Self::Rgb => Some(sys::Frame::Rgb),
+            Self::Red => Some(sys::Frame::Red),
+            Self::Green => Some(sys::Frame::Green),
+            Self::Blue => Some(sys::Frame::Blue),
+            Self::Unsupported => None,
+        }
+    }
 }
 
 impl From<sys::Frame> for FrameFormat {
@@ -297,4 +1158,71 @@ impl<S: WithSane> DeviceHandle<S> {
     pub fn get_parameters(&self) -> Result<FrameParameters, Error> {
         self.inner.get_parameters()
     }
+
+    /// Estimates the parameters of every frame of the next image. Backends which acquire
+    /// the red, green and blue bands in separate passes only describe the first frame,
+    /// in which case three parameter sets with the same dimensions are returned.
+    ///
+    /// This is only an estimate, as the parameters may change once the scan is
+    /// [started][`Self::start`].
+    pub fn estimate_all_frames(&self) -> Result<Vec<FrameParameters>, Error> {
+        let params = self.get_parameters()?;
+        if !matches!(
+            params.format(),
+            FrameFormat::Red | FrameFormat::Green | FrameFormat::Blue
+        ) {
+            return Ok(vec![params]);
+        }
+        let bands = [sys::Frame::Red, sys::Frame::Green, sys::Frame::Blue];
+        Ok(bands
+            .into_iter()
+            .enumerate()
+            .map(|(i, format)| FrameParameters {
+                format,
+                last_frame: i == bands.len() - 1,
+                ..params
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_exit::TEST_INSTANCE_LOCK, ControlInfo, SaneStr};
+
+    #[test]
+    fn frame_format_sys_round_trip() {
+        use FrameFormat::*;
+        for format in [Gray, Rgb, Red, Green, Blue] {
+            let sys = format.to_sys().unwrap();
+            assert_eq!(FrameFormat::from(sys), format);
+            let params = FrameParameters::new(format, true, 1, 1, None, 8);
+            assert_eq!(params.sys_format(), sys);
+            assert_eq!(params.format(), format);
+        }
+        assert_eq!(Unsupported.to_sys(), None);
+        assert_eq!(FrameFormat::from(sys::Frame(42)), Unsupported);
+    }
+
+    #[test]
+    #[ignore = "requires the SANE test backend"]
+    fn reading_parameters_clears_dirty_flag() {
+        let _lock = TEST_INSTANCE_LOCK.lock();
+        let (sane, _) = Sane::init_no_auth().unwrap();
+        let device = sane.connect(SaneStr::from_cstr(c"test:0")).unwrap();
+        device.inner.option_changed(ControlInfo::RELOAD_PARAMS);
+        assert!(device.parameters_dirty());
+
+        let mut reader = device.scan_blocking();
+        let mut frame = reader.next_frame().unwrap().unwrap();
+        assert!(!frame.scanner.device().parameters_dirty());
+        frame
+            .scanner
+            .device()
+            .inner
+            .option_changed(ControlInfo::RELOAD_PARAMS);
+        frame.refresh_parameters().unwrap();
+        assert!(!frame.scanner.device().parameters_dirty());
+    }
 }
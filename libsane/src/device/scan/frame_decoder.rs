@@ -1,13 +1,20 @@
+mod transform;
+
 use core::fmt;
-use std::mem::MaybeUninit;
+use std::{io, mem::MaybeUninit};
 
-use super::FrameParameters;
+use super::{FrameFormat, FrameParameters};
 use crate::{slice_util::slice_as_maybe_uninit, sys};
 
 #[derive(Debug, Clone)]
 pub struct Builder {
     buffer: Vec<u8>,
     black_and_white_as_bytes: bool,
+    invert_lineart: bool,
+    multi_band_channels: u8,
+    gamma: f32,
+    normalize_to_8bit: bool,
+    expected_lines: Option<u32>,
 }
 
 impl Default for Builder {
@@ -21,6 +28,11 @@ impl Builder {
         Self {
             buffer: Vec::new(),
             black_and_white_as_bytes: false,
+            invert_lineart: true,
+            multi_band_channels: 0,
+            gamma: 1.0,
+            normalize_to_8bit: false,
+            expected_lines: None,
         }
     }
 
@@ -31,6 +43,12 @@ impl Builder {
             width: 0,
             height: 0,
             black_and_white_as_bytes: self.black_and_white_as_bytes,
+            invert_lineart: self.invert_lineart,
+            multi_band_channels: self.multi_band_channels,
+            gamma: self.gamma,
+            normalize_to_8bit: self.normalize_to_8bit,
+            expected_lines: self.expected_lines,
+            gamma_lut: None,
         }
     }
 
@@ -43,6 +61,75 @@ impl Builder {
         }
     }
 
+    /// SANE transmits black and white images with a set bit for black and a cleared bit
+    /// for white. By default, the decoder inverts the pixels, so that `1` is white like in
+    /// gray images. Setting this to false preserves the polarity of SANE, which is useful
+    /// for formats where `1` is black. The polarity is recorded in
+    /// [`DecodedImageFormat::BlackAndWhite`].
+    ///
+    /// This has no effect on images [normalized][`Self::normalize_to_8bit`] to gray, where
+    /// white is always `255`.
+    pub fn invert_lineart(self, do_it: bool) -> Self {
+        Self {
+            invert_lineart: do_it,
+            ..self
+        }
+    }
+
+    /// Decodes frames with a format unknown to SANE as pixel-interleaved data with the
+    /// given amount of bands, producing a [`DecodedImageFormat::Multi`] image. By default
+    /// (`0`), such frames are rejected as unsupported.
+    ///
+    /// SANE itself does not standardize CMYK or other multi-band formats, so the caller
+    /// must know how the backend represents them, for example from its documentation.
+    pub fn multi_band_channels(self, channels: u8) -> Self {
+        Self {
+            multi_band_channels: channels,
+            ..self
+        }
+    }
+
+    /// Applies gamma correction to 8 and 16 bit gray and RGB samples while decoding.
+    /// Every sample is normalized to the range `0..=1` and raised to the power of
+    /// `gamma`, so a value of `2.2` linearizes typical scanner output. The default of
+    /// `1.0` leaves the samples unchanged.
+    ///
+    /// # Panics
+    /// If `gamma` is not a positive finite number.
+    pub fn gamma(self, gamma: f32) -> Self {
+        assert!(
+            gamma.is_finite() && gamma > 0.0,
+            "gamma must be a positive finite number"
+        );
+        Self { gamma, ..self }
+    }
+
+    /// Converts all samples to 8 bits, so that the decoded image is either
+    /// [`Gray`][`DecodedImageFormat::Gray`] or [`Rgb`][`DecodedImageFormat::Rgb`] with a
+    /// single byte per channel. Black and white pixels become `0` or `255` and only the
+    /// most significant byte of wider samples is kept. By default, the native depth is
+    /// preserved.
+    pub fn normalize_to_8bit(self, do_it: bool) -> Self {
+        Self {
+            normalize_to_8bit: do_it,
+            ..self
+        }
+    }
+
+    /// The number of lines of the image, if it is known in advance although the
+    /// [parameters][`FrameParameters::lines`] of the frames do not specify it, for example
+    /// from an earlier estimate. Frames with more lines are rejected with
+    /// [`FrameDecodeError::TooManyLines`], which prevents runaway buffers on misbehaving
+    /// backends, and [`FrameDecoder::reserve_for`] pre-allocates the buffer accordingly.
+    ///
+    /// By default, the number of lines is inferred from the frame data.
+    pub fn expected_lines(self, lines: u32) -> Self {
+        Self {
+            expected_lines: Some(lines),
+            ..self
+        }
+    }
+
     pub fn with_buffer(self, buffer: Vec<u8>) -> Self {
         Self { buffer, ..self }
     }
@@ -55,6 +142,13 @@ pub struct FrameDecoder {
     width: u32,
     height: u32,
     black_and_white_as_bytes: bool,
+    invert_lineart: bool,
+    multi_band_channels: u8,
+    gamma: f32,
+    normalize_to_8bit: bool,
+    expected_lines: Option<u32>,
+    /// Built on the first write, see [`GammaLut`].
+    gamma_lut: Option<GammaLut>,
 }
 
 impl Default for FrameDecoder {
@@ -75,6 +169,12 @@ impl FrameDecoder {
             width: 0,
             height: 0,
             black_and_white_as_bytes: false,
+            invert_lineart: true,
+            multi_band_channels: 0,
+            gamma: 1.0,
+            normalize_to_8bit: false,
+            expected_lines: None,
+            gamma_lut: None,
         }
     }
 }
@@ -84,6 +184,32 @@ impl FrameDecoder {
         self.state.is_done()
     }
 
+    /// The red, green or blue sub-frames that are still needed to complete an image
+    /// which is transmitted in three passes. This is empty if no sub-frame has been
+    /// written yet, or if the image is not transmitted in separate sub-frames.
+    pub const fn missing_channels(&self) -> &'static [FrameFormat] {
+        use FrameFormat::{Blue, Green, Red};
+        let FrameDecoderState::RgbParts {
+            has_red,
+            has_green,
+            has_blue,
+            ..
+        } = self.state
+        else {
+            return &[];
+        };
+        match (has_red, has_green, has_blue) {
+            (false, false, false) => &[Red, Green, Blue],
+            (false, false, true) => &[Red, Green],
+            (false, true, false) => &[Red, Blue],
+            (false, true, true) => &[Red],
+            (true, false, false) => &[Green, Blue],
+            (true, false, true) => &[Green],
+            (true, true, false) => &[Blue],
+            (true, true, true) => &[],
+        }
+    }
+
     pub fn into_image(self) -> Result<DecodedImage, Vec<u8>> {
         if let FrameDecoderState::Done(format) = self.state {
             Ok(DecodedImage {
@@ -97,6 +223,46 @@ impl FrameDecoder {
         }
     }
 
+    /// Pre-allocates the internal buffer for an image with the given parameters, which
+    /// avoids repeated reallocations in [`Self::write`].
+    ///
+    /// This is only a hint. If the number of lines is unknown and no
+    /// [expected number of lines][`Builder::expected_lines`] was configured, or the
+    /// parameters are not supported, nothing is reserved. If the estimate turns out to be wrong,
+    /// [`Self::write`] will still reserve the missing capacity.
+    pub fn reserve_for(&mut self, params: &FrameParameters) {
+        if !matches!(self.state, FrameDecoderState::Initial) {
+            return;
+        }
+        let Some(lines) = params.lines.or(self.expected_lines) else {
+            return;
+        };
+        let Some(pixels) = (params.pixels_per_line as usize).checked_mul(lines as usize) else {
+            return;
+        };
+        let len = match params.sys_format() {
+            sys::Frame::Gray if params.depth == 1 => {
+                if self.black_and_white_as_bytes || self.normalize_to_8bit {
+                    Some(pixels)
+                } else {
+                    Some(pixels / 8)
+                }
+            }
+            sys::Frame::Gray => pixels.checked_mul(self.output_bytes(params.depth)),
+            sys::Frame::Rgb | sys::Frame::Red | sys::Frame::Green | sys::Frame::Blue => pixels
+                .checked_mul(3)
+                .and_then(|len| len.checked_mul(self.output_bytes(params.depth))),
+            _ if self.is_multi_band(params) => pixels
+                .checked_mul(self.multi_band_channels.into())
+                .and_then(|len| len.checked_mul(self.output_bytes(params.depth))),
+            _ => return,
+        };
+        // The parameters are provided by the backend, an absurd size must not abort.
+        if let Some(len) = len {
+            let _ = self.buffer.try_reserve_exact(len);
+        }
+    }
+
     pub fn write(
         &mut self,
         frame: &[u8],
@@ -105,8 +271,45 @@ impl FrameDecoder {
         if self.is_done() {
             return Err(FrameDecodeError::AlreadyDone);
         }
+        // The lookup table is reused for all frames with the same depth.
+        let lut = self
+            .gamma_lut
+            .take()
+            .filter(|lut| lut.depth() == params.depth)
+            .or_else(|| GammaLut::new(self.gamma, params.depth));
+        let result = self.write_with_lut(frame, params, lut.as_ref());
+        self.gamma_lut = lut;
+        result
+    }
 
-        if params.depth == 0 {
+    fn write_with_lut(
+        &mut self,
+        frame: &[u8],
+        params: &FrameParameters,
+        lut: Option<&GammaLut>,
+    ) -> Result<(), FrameDecodeError> {
+        if params.depth == 0 || params.bytes_per_line == 0 {
+            return Err(FrameDecodeError::InvalidParameters);
+        }
+
+        let multi_band = self.is_multi_band(params);
+        let samples = SampleConversion {
+            bytes: (params.depth / 8) as usize,
+            lut,
+            to_8bit: self.normalize_to_8bit,
+        };
+        let gamma = if lut.is_some() { self.gamma } else { 1.0 };
+        // Every line must contain all of its pixels, which also bounds the size of the
+        // decoded image by the size of the frame.
+        let samples_per_pixel = match params.sys_format() {
+            sys::Frame::Rgb => 3,
+            _ if multi_band => self.multi_band_channels.into(),
+            _ => 1,
+        };
+        let line_bytes = (params.pixels_per_line as u64)
+            .checked_mul(samples_per_pixel * params.depth as u64)
+            .map(|bits| bits.div_ceil(8));
+        if line_bytes.is_none_or(|len| len > params.bytes_per_line as u64) {
             return Err(FrameDecodeError::InvalidParameters);
         }
 
@@ -123,17 +326,28 @@ impl FrameDecoder {
         if params.lines.is_some_and(|l| l != f_height) {
             return Err(FrameDecodeError::InvalidParameters);
         }
+        if let Some(expected) = self.expected_lines.filter(|&l| l < f_height) {
+            return Err(FrameDecodeError::TooManyLines {
+                expected,
+                got: f_height,
+            });
+        }
 
         match (&mut self.state, params.sys_format()) {
             // black and white
             (FrameDecoderState::Initial, sys::Frame::Gray) if params.depth == 1 => {
                 if params.pixels_per_line & 0b111 != 0 {
                     // only supports whole byte lines
-                    return Err(FrameDecodeError::UnsupportedParameters);
+                    return Err(FrameDecodeError::unsupported(params));
                 }
                 let dst_len;
-                if self.black_and_white_as_bytes {
-                    dst_len = f_width as usize * f_height as usize;
+                let format;
+                // Note: SANE uses 0 = white, 1 = black
+                let white_is_one = self.invert_lineart || self.normalize_to_8bit;
+                if self.black_and_white_as_bytes || self.normalize_to_8bit {
+                    let high = if self.normalize_to_8bit { u8::MAX } else { 1 };
+                    let (black, white) = if white_is_one { (0, high) } else { (high, 0) };
+                    dst_len = image_len(f_width, f_height, 1)?;
                     self.buffer.reserve_exact(dst_len);
                     let bytes = frame
                         .chunks_exact(params.bytes_per_line as usize)
@@ -141,85 +355,80 @@ impl FrameDecoder {
                     let dst = &mut self.buffer.spare_capacity_mut()[..dst_len];
                     for (i, byte) in bytes.enumerate() {
                         for j in 0..8 {
-                            // Note: 0 = white, 1 = black
-                            dst[8 * i + j] =
-                                MaybeUninit::new(if *byte & (0x80 >> j) != 0 { 0 } else { 1 });
+                            dst[8 * i + j] = MaybeUninit::new(if *byte & (0x80 >> j) != 0 {
+                                black
+                            } else {
+                                white
+                            });
                         }
                     }
+                    format = if self.normalize_to_8bit {
+                        DecodedImageFormat::Gray {
+                            bytes_per_pixel: 1,
+                            gamma: 1.0,
+                        }
+                    } else {
+                        DecodedImageFormat::BlackAndWhite {
+                            packed: false,
+                            white_is_one,
+                        }
+                    };
                 } else {
-                    dst_len = f_width as usize * f_height as usize / 8;
+                    dst_len = image_len(f_width, f_height, 1)? / 8;
                     self.buffer.reserve_exact(dst_len);
                     let bytes = frame
                         .chunks_exact(params.bytes_per_line as usize)
                         .flat_map(|line| line[..(params.pixels_per_line / 8) as usize].iter());
+                    let mask = if white_is_one { u8::MAX } else { 0 };
                     for (dst, src) in self.buffer.spare_capacity_mut()[..dst_len]
                         .iter_mut()
                         .zip(bytes)
                     {
-                        // Note: 0 = white, 1 = black
-                        *dst = MaybeUninit::new(!*src);
+                        *dst = MaybeUninit::new(*src ^ mask);
                     }
+                    format = DecodedImageFormat::BlackAndWhite {
+                        packed: true,
+                        white_is_one,
+                    };
                 }
                 // SAFETY: dst_len spare capacity was fully initialized
                 unsafe { self.buffer.set_len(self.buffer.len() + dst_len) }
                 self.width = f_width;
                 self.height = f_height;
-                self.state = FrameDecoderState::Done(DecodedImageFormat::BlackAndWhite);
+                self.state = FrameDecoderState::Done(format);
                 Ok(())
             }
             // grayscale
             (FrameDecoderState::Initial, sys::Frame::Gray) => {
-                if params.depth & 0b111 != 0 {
-                    // only supports whole byte channels
-                    return Err(FrameDecodeError::UnsupportedParameters);
-                }
-                let bytes_per_pixel = params.depth / 8;
-                let bytes = frame
-                    .chunks_exact(params.bytes_per_line as usize)
-                    .flat_map(|line| {
-                        line[..params.pixels_per_line as usize * bytes_per_pixel as usize].iter()
-                    });
-                let dst_len = f_width as usize * f_height as usize * bytes_per_pixel as usize;
-                self.buffer.reserve_exact(dst_len);
-                for (dst, src) in self.buffer.spare_capacity_mut()[..dst_len]
-                    .iter_mut()
-                    .zip(bytes)
-                {
-                    *dst = MaybeUninit::new(*src);
-                }
-                // SAFETY: dst_len spare capacity was fully initialized
-                unsafe { self.buffer.set_len(self.buffer.len() + dst_len) }
-                self.width = f_width;
-                self.height = f_height;
-                self.state = FrameDecoderState::Done(DecodedImageFormat::Gray { bytes_per_pixel });
+                let bytes_per_pixel = self.write_interleaved(frame, params, 1, samples)?;
+                self.state = FrameDecoderState::Done(DecodedImageFormat::Gray {
+                    bytes_per_pixel,
+                    gamma,
+                });
                 Ok(())
             }
             // rgb
             (FrameDecoderState::Initial, sys::Frame::Rgb) => {
-                if params.depth & 0b111 != 0 {
-                    // only supports whole byte channels
-                    return Err(FrameDecodeError::UnsupportedParameters);
-                }
-                let bytes_per_channel = params.depth / 8;
-                let bytes_per_pixel = bytes_per_channel * 3;
-                let bytes = frame
-                    .chunks_exact(params.bytes_per_line as usize)
-                    .flat_map(|line| {
-                        line[..params.pixels_per_line as usize * bytes_per_pixel as usize].iter()
-                    });
-                let dst_len = f_width as usize * f_height as usize * bytes_per_pixel as usize;
-                self.buffer.reserve_exact(dst_len);
-                for (dst, src) in self.buffer.spare_capacity_mut()[..dst_len]
-                    .iter_mut()
-                    .zip(bytes)
-                {
-                    *dst = MaybeUninit::new(*src);
-                }
-                // SAFETY: spare capacity was fully initialized
-                unsafe { self.buffer.set_len(self.buffer.len() + dst_len) }
-                self.width = f_width;
-                self.height = f_height;
-                self.state = FrameDecoderState::Done(DecodedImageFormat::Rgb { bytes_per_channel });
+                let bytes_per_channel = self.write_interleaved(frame, params, 3, samples)?;
+                self.state = FrameDecoderState::Done(DecodedImageFormat::Rgb {
+                    bytes_per_channel,
+                    gamma,
+                });
+                Ok(())
+            }
+            // multi-band
+            (FrameDecoderState::Initial, _) if multi_band => {
+                let channels = self.multi_band_channels;
+                // the meaning of the bands is unknown, so gamma correction is not applied
+                let samples = SampleConversion {
+                    lut: None,
+                    ..samples
+                };
+                let bytes_per_channel = self.write_interleaved(frame, params, channels, samples)?;
+                self.state = FrameDecoderState::Done(DecodedImageFormat::Multi {
+                    channels,
+                    bytes_per_channel,
+                });
                 Ok(())
             }
             // rgb parts
@@ -229,26 +438,26 @@ impl FrameDecoder {
             ) => {
                 if params.depth & 0b111 != 0 {
                     // only supports whole byte channels
-                    return Err(FrameDecodeError::UnsupportedParameters);
+                    return Err(FrameDecodeError::unsupported(params));
                 }
                 let bytes_per_channel = params.depth / 8;
-                let bytes_per_pixel = bytes_per_channel * 3;
-                let offset = bytes_per_pixel as usize
+                let bytes_per_pixel = samples.output_bytes() * 3;
+                let offset = samples.output_bytes()
                     * match channel {
                         sys::Frame::Red => 0,
                         sys::Frame::Green => 1,
                         sys::Frame::Blue => 2,
                         _ => unreachable!(),
                     };
-                let dst_len = f_width as usize * f_height as usize * bytes_per_pixel as usize;
+                let dst_len = image_len(f_width, f_height, bytes_per_pixel)?;
                 self.buffer.reserve_exact(dst_len);
                 Self::write_channel(
                     &mut self.buffer.spare_capacity_mut()[..dst_len],
                     frame,
                     params.bytes_per_line as usize,
                     f_width as usize,
-                    bytes_per_channel as usize,
                     offset,
+                    samples,
                 );
                 self.width = f_width;
                 self.height = f_height;
@@ -291,36 +500,49 @@ impl FrameDecoder {
                 if *has_chan {
                     return Err(FrameDecodeError::DuplicateChannel);
                 }
+                if f_width == self.width && f_height != self.height {
+                    return Err(FrameDecodeError::SubFrameHeightMismatch {
+                        expected: self.height,
+                        got: f_height,
+                    });
+                }
                 if f_width != self.width || f_height != self.height {
-                    return Err(FrameDecodeError::UnexpectedParameters);
+                    return Err(FrameDecodeError::UnexpectedParameters {
+                        expected: (self.width, self.height),
+                        got: (f_width, f_height),
+                    });
                 }
                 if params.depth & 0b111 != 0 || params.depth / 8 != *bytes_per_channel {
                     // only supports whole byte channels
-                    return Err(FrameDecodeError::UnexpectedParameters);
+                    return Err(FrameDecodeError::UnexpectedDepth {
+                        expected: *bytes_per_channel * 8,
+                        got: params.depth,
+                    });
                 }
 
-                let bytes_per_pixel = *bytes_per_channel as usize * 3;
-                let offset = bytes_per_pixel
+                let bytes_per_pixel = samples.output_bytes() * 3;
+                let offset = samples.output_bytes()
                     * match channel {
                         sys::Frame::Red => 0,
                         sys::Frame::Green => 1,
                         sys::Frame::Blue => 2,
                         _ => unreachable!(),
                     };
-                let dst_len = f_width as usize * f_height as usize * bytes_per_pixel;
+                let dst_len = image_len(f_width, f_height, bytes_per_pixel)?;
                 Self::write_channel(
                     &mut self.buffer.spare_capacity_mut()[..dst_len],
                     frame,
                     params.bytes_per_line as usize,
                     f_width as usize,
-                    *bytes_per_channel as usize,
                     offset,
+                    samples,
                 );
                 if *has_other1 && *has_other2 {
                     // SAFETY: All pixel channels were fully initialized
                     unsafe { self.buffer.set_len(self.buffer.len() + dst_len) };
                     self.state = FrameDecoderState::Done(DecodedImageFormat::Rgb {
-                        bytes_per_channel: *bytes_per_channel,
+                        bytes_per_channel: samples.output_bytes() as u32,
+                        gamma,
                     })
                 } else {
                     *has_chan = true;
@@ -328,30 +550,195 @@ impl FrameDecoder {
                 Ok(())
             }
             // other unknown frame format
-            _ => Err(FrameDecodeError::UnsupportedParameters),
+            _ => Err(FrameDecodeError::unsupported(params)),
         }
     }
 
+    /// Whether the frame has a format unknown to SANE and should be decoded as
+    /// multi-band data, see [`Builder::multi_band_channels`].
+    fn is_multi_band(&self, params: &FrameParameters) -> bool {
+        self.multi_band_channels != 0 && params.format() == FrameFormat::Unsupported
+    }
+
+    /// Amount of bytes per channel in the decoded image for the given bit depth.
+    fn output_bytes(&self, depth: u32) -> usize {
+        if self.normalize_to_8bit {
+            1
+        } else {
+            (depth / 8) as usize
+        }
+    }
+
+    /// Copies a frame of pixel-interleaved channels into the buffer, converting every
+    /// sample. Returns the amount of bytes per channel in the decoded image.
+    fn write_interleaved(
+        &mut self,
+        frame: &[u8],
+        params: &FrameParameters,
+        channels: u8,
+        samples: SampleConversion,
+    ) -> Result<u32, FrameDecodeError> {
+        if params.depth & 0b111 != 0 {
+            // only supports whole byte channels
+            return Err(FrameDecodeError::unsupported(params));
+        }
+        let f_width = params.pixels_per_line;
+        let f_height = frame.len() as u32 / params.bytes_per_line;
+        let src_line_len = f_width as usize * samples.bytes * channels as usize;
+        let bytes_per_pixel = samples.output_bytes() * channels as usize;
+        let line_len = f_width as usize * bytes_per_pixel;
+        let dst_len = image_len(f_width, f_height, bytes_per_pixel)?;
+        self.buffer.reserve_exact(dst_len);
+        let dst_lines =
+            self.buffer.spare_capacity_mut()[..dst_len].chunks_exact_mut(line_len.max(1));
+        for (dst, src) in dst_lines.zip(frame.chunks_exact(params.bytes_per_line as usize)) {
+            samples.copy(dst, &src[..src_line_len]);
+        }
+        // SAFETY: spare capacity was fully initialized
+        unsafe { self.buffer.set_len(self.buffer.len() + dst_len) }
+        self.width = f_width;
+        self.height = f_height;
+        Ok(samples.output_bytes() as u32)
+    }
+
     fn write_channel(
         dst: &mut [MaybeUninit<u8>],
         frame: &[u8],
         bytes_per_line: usize,
         width: usize,
-        bytes_per_channel: usize,
         offset: usize,
+        samples: SampleConversion,
     ) {
         let channels = frame
             .chunks_exact(bytes_per_line)
-            .flat_map(|line| line[..width * bytes_per_channel].chunks_exact(bytes_per_channel));
+            .flat_map(|line| line[..width * samples.bytes].chunks_exact(samples.bytes));
+        let bytes_per_channel = samples.output_bytes();
         let dst_channels = dst
             .chunks_exact_mut(3 * bytes_per_channel)
             .map(|pixel| &mut pixel[offset..offset + bytes_per_channel]);
         for (dst, src) in dst_channels.zip(channels) {
-            dst.copy_from_slice(slice_as_maybe_uninit(src));
+            samples.copy(dst, src);
         }
     }
 }
 
+/// Precomputed gamma correction for every possible value of an 8 or 16 bit sample, see
+/// [`Builder::gamma`].
+#[derive(Clone)]
+enum GammaLut {
+    Byte(Vec<u8>),
+    Word(Vec<u16>),
+}
+
+impl GammaLut {
+    /// Returns `None` if no correction is needed or the samples are not 8 or 16 bits.
+    fn new(gamma: f32, depth: u32) -> Option<Self> {
+        if gamma == 1.0 {
+            return None;
+        }
+        let curve = |value: u16, max: u16| {
+            let max = f64::from(max);
+            ((f64::from(value) / max).powf(gamma.into()) * max).round()
+        };
+        match depth {
+            8 => Some(Self::Byte(
+                (0..=u8::MAX.into())
+                    .map(|v| curve(v, u8::MAX.into()) as u8)
+                    .collect(),
+            )),
+            16 => Some(Self::Word(
+                (0..=u16::MAX).map(|v| curve(v, u16::MAX) as u16).collect(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Bit depth of the samples this table applies to.
+    const fn depth(&self) -> u32 {
+        match self {
+            Self::Byte(_) => 8,
+            Self::Word(_) => 16,
+        }
+    }
+}
+
+impl fmt::Debug for GammaLut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The table itself is too large to be useful
+        f.debug_tuple(stringify!(GammaLut))
+            .field(&self.depth())
+            .finish()
+    }
+}
+
+/// Conversion applied to every sample while it is copied into the decoded image.
+#[derive(Clone, Copy)]
+struct SampleConversion<'a> {
+    /// Amount of bytes per sample in the frame.
+    bytes: usize,
+    lut: Option<&'a GammaLut>,
+    /// Whether only the most significant byte of every sample is kept.
+    to_8bit: bool,
+}
+
+impl SampleConversion<'_> {
+    /// Amount of bytes per sample in the decoded image.
+    const fn output_bytes(&self) -> usize {
+        if self.to_8bit {
+            1
+        } else {
+            self.bytes
+        }
+    }
+
+    /// Copies the samples from `src` to `dst`, which must be sized for the samples in
+    /// the frame and decoded image respectively.
+    fn copy(&self, dst: &mut [MaybeUninit<u8>], src: &[u8]) {
+        match (self.lut, self.to_8bit) {
+            (Some(GammaLut::Byte(lut)), _) => {
+                for (dst, src) in dst.iter_mut().zip(src) {
+                    *dst = MaybeUninit::new(lut[*src as usize]);
+                }
+            }
+            (Some(GammaLut::Word(lut)), to_8bit) => {
+                // SANE transmits 16 bit samples in native byte order
+                let src = src
+                    .chunks_exact(2)
+                    .map(|src| lut[u16::from_ne_bytes([src[0], src[1]]) as usize]);
+                if to_8bit {
+                    for (dst, sample) in dst.iter_mut().zip(src) {
+                        *dst = MaybeUninit::new((sample >> 8) as u8);
+                    }
+                } else {
+                    for (dst, sample) in dst.chunks_exact_mut(2).zip(src) {
+                        dst.copy_from_slice(slice_as_maybe_uninit(&sample.to_ne_bytes()));
+                    }
+                }
+            }
+            (None, true) if self.bytes > 1 => {
+                let msb = if cfg!(target_endian = "little") {
+                    self.bytes - 1
+                } else {
+                    0
+                };
+                for (dst, src) in dst.iter_mut().zip(src.chunks_exact(self.bytes)) {
+                    *dst = MaybeUninit::new(src[msb]);
+                }
+            }
+            (None, _) => dst.copy_from_slice(slice_as_maybe_uninit(src)),
+        }
+    }
+}
+
+/// Computes the size of a decoded image in bytes, which may not fit into memory if the
+/// parameters are absurd.
+fn image_len(width: u32, height: u32, bytes_per_pixel: usize) -> Result<usize, FrameDecodeError> {
+    (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|pixels| pixels.checked_mul(bytes_per_pixel))
+        .ok_or(FrameDecodeError::InvalidParameters)
+}
+
 #[derive(Debug, Clone, Copy)]
 enum FrameDecoderState {
     Initial,
@@ -378,38 +765,434 @@ pub struct DecodedImage {
     pub height: u32,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl DecodedImage {
+    /// Amount of bytes of one row of pixels in [`Self::data`]. Rows of packed bitmaps are
+    /// padded to whole bytes, other rows are not padded.
+    pub fn row_stride(&self) -> usize {
+        match self.format.bytes_per_pixel() {
+            Some(bytes_per_pixel) => self.width as usize * bytes_per_pixel,
+            None => (self.width as usize).div_ceil(8),
+        }
+    }
+
+    /// Whether this is a black and white image stored as a packed big-endian bitmap,
+    /// see [`DecodedImageFormat::BlackAndWhite`].
+    pub fn is_packed_bitmap(&self) -> bool {
+        matches!(
+            self.format,
+            DecodedImageFormat::BlackAndWhite { packed: true, .. }
+        )
+    }
+}
+
+/// Reads a pixel of a packed big-endian bitmap.
+pub(crate) fn get_bit(row: &[u8], x: usize) -> bool {
+    row[x / 8] & (0x80 >> (x % 8)) != 0
+}
+
+/// Writes a pixel of a packed big-endian bitmap.
+pub(crate) fn set_bit(row: &mut [u8], x: usize, value: bool) {
+    if value {
+        row[x / 8] |= 0x80 >> (x % 8);
+    } else {
+        row[x / 8] &= !(0x80 >> (x % 8));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
 pub enum DecodedImageFormat {
-    /// Black and white images are represented as a packed big-endian bitmap unless
-    /// [`FrameDecoder::decode_black_and_white_as_bytes`] was set to `true`, in which case
-    /// every pixel is a byte with value `0` or `1`.
-    BlackAndWhite,
-    /// Gray pixel data with the given amount of bytes per pixel.
-    Gray { bytes_per_pixel: u32 },
-    /// RGB pixel data wit the given amount of bytes per color channel.
-    Rgb { bytes_per_channel: u32 },
+    /// Black and white pixel data. If `packed`, the pixels are a big-endian bitmap with
+    /// every row padded to whole bytes, otherwise every pixel is a byte with value `0` or
+    /// `1`, see [`Builder::decode_black_and_white_as_bytes`]. If `white_is_one`, white
+    /// pixels are `1` and black pixels are `0`, otherwise it is the other way around, see
+    /// [`Builder::invert_lineart`].
+    BlackAndWhite { packed: bool, white_is_one: bool },
+    /// Gray pixel data with the given amount of bytes per pixel. `gamma` is the
+    /// [correction][`Builder::gamma`] applied by the decoder, or `1.0` if none was
+    /// applied.
+    Gray { bytes_per_pixel: u32, gamma: f32 },
+    /// RGB pixel data wit the given amount of bytes per color channel. `gamma` is the
+    /// [correction][`Builder::gamma`] applied by the decoder, or `1.0` if none was
+    /// applied.
+    Rgb { bytes_per_channel: u32, gamma: f32 },
+    /// Pixel-interleaved data with an arbitrary amount of channels, such as CMYK. This
+    /// is only produced if [`Builder::multi_band_channels`] was set, the meaning of the
+    /// channels is up to the backend.
+    Multi {
+        channels: u8,
+        bytes_per_channel: u32,
+    },
+}
+
+impl DecodedImageFormat {
+    /// Amount of bytes used by a single pixel. Returns `None` for a packed
+    /// [`Self::BlackAndWhite`] image, as a pixel only takes up a single bit.
+    pub const fn bytes_per_pixel(&self) -> Option<usize> {
+        match *self {
+            Self::BlackAndWhite { packed: true, .. } => None,
+            Self::BlackAndWhite { packed: false, .. } => Some(1),
+            Self::Gray {
+                bytes_per_pixel, ..
+            } => Some(bytes_per_pixel as usize),
+            Self::Rgb {
+                bytes_per_channel, ..
+            } => Some(bytes_per_channel as usize * 3),
+            Self::Multi {
+                channels,
+                bytes_per_channel,
+            } => Some(bytes_per_channel as usize * channels as usize),
+        }
+    }
+
+    /// Amount of color channels per pixel.
+    pub const fn channels(&self) -> u8 {
+        match self {
+            Self::BlackAndWhite { .. } | Self::Gray { .. } => 1,
+            Self::Rgb { .. } => 3,
+            Self::Multi { channels, .. } => *channels,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum FrameDecodeError {
     AlreadyDone,
     DuplicateChannel,
-    UnexpectedParameters,
-    UnsupportedParameters,
+    /// The dimensions `(width, height)` of this frame mismatch the predecessor.
+    UnexpectedParameters {
+        expected: (u32, u32),
+        got: (u32, u32),
+    },
+    /// The height of this red, green or blue sub-frame mismatches the predecessor. This
+    /// can happen with three-pass scanners which do not know the number of lines in
+    /// advance.
+    SubFrameHeightMismatch {
+        expected: u32,
+        got: u32,
+    },
+    /// This frame has more lines than [expected][`Builder::expected_lines`].
+    TooManyLines {
+        expected: u32,
+        got: u32,
+    },
+    /// The bit depth of this frame mismatches the predecessor.
+    UnexpectedDepth {
+        expected: u32,
+        got: u32,
+    },
+    /// This combination of frame format and bit depth is not supported.
+    UnsupportedParameters {
+        format: sys::Frame,
+        depth: u32,
+    },
     InvalidParameters,
 }
 
+impl FrameDecodeError {
+    fn unsupported(params: &FrameParameters) -> Self {
+        Self::UnsupportedParameters {
+            format: params.sys_format(),
+            depth: params.depth,
+        }
+    }
+}
+
 impl fmt::Display for FrameDecodeError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let msg = match self {
-            Self::AlreadyDone => "already received all frames",
-            Self::DuplicateChannel => "channel was already received",
-            Self::UnexpectedParameters => "parameters of this frame mismatch the predecessor",
-            Self::UnsupportedParameters => "frame parameters are not supported by this decoder",
-            Self::InvalidParameters => "frame parameters are invalid",
-        };
-        f.write_str(msg)
+        match *self {
+            Self::AlreadyDone => f.write_str("already received all frames"),
+            Self::DuplicateChannel => f.write_str("channel was already received"),
+            Self::UnexpectedParameters { expected, got } => write!(
+                f,
+                "frame dimensions {}x{} mismatch the predecessor ({}x{})",
+                got.0, got.1, expected.0, expected.1,
+            ),
+            Self::SubFrameHeightMismatch { expected, got } => write!(
+                f,
+                "sub-frame has {got} lines, but the previous sub-frame had {expected} lines"
+            ),
+            Self::TooManyLines { expected, got } => write!(
+                f,
+                "frame has {got} lines, but at most {expected} lines were expected"
+            ),
+            Self::UnexpectedDepth { expected, got } => write!(
+                f,
+                "frame depth {got} mismatches the predecessor ({expected})"
+            ),
+            Self::UnsupportedParameters { format, depth } => {
+                f.write_str("frame parameters are not supported by this decoder (format ")?;
+                match FrameFormat::from(format) {
+                    FrameFormat::Unsupported => write!(f, "{}", format.0)?,
+                    known => write!(f, "{known:?}")?,
+                }
+                write!(f, ", depth {depth})")
+            }
+            Self::InvalidParameters => f.write_str("frame parameters are invalid"),
+        }
     }
 }
 
 impl std::error::Error for FrameDecodeError {}
+
+impl From<FrameDecodeError> for io::Error {
+    fn from(value: FrameDecodeError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(
+        builder: Builder,
+        frames: &[(FrameFormat, &[u8])],
+        width: u32,
+        depth: u32,
+    ) -> DecodedImage {
+        let mut decoder = builder.build();
+        for &(format, data) in frames {
+            let samples = if format == FrameFormat::Rgb { 3 } else { 1 };
+            let bytes_per_line = (width * samples * depth).div_ceil(8);
+            let params = FrameParameters::new(format, false, bytes_per_line, width, None, depth);
+            decoder.write(data, &params).unwrap();
+        }
+        decoder.into_image().unwrap()
+    }
+
+    #[test]
+    fn gamma_one_is_no_op() {
+        let data: Vec<u8> = (0..=u8::MAX).collect();
+        for depth in [8, 16] {
+            let width = 256 / (depth / 8);
+            let image = decode(
+                FrameDecoder::builder().gamma(1.0),
+                &[(FrameFormat::Gray, &data)],
+                width,
+                depth,
+            );
+            assert_eq!(image.data, data);
+            assert_eq!(
+                image.format,
+                DecodedImageFormat::Gray {
+                    bytes_per_pixel: depth / 8,
+                    gamma: 1.0,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn gamma_is_recorded_and_applied_to_all_sub_frames() {
+        let frames = [
+            (FrameFormat::Red, &[0, 128, 255][..]),
+            (FrameFormat::Green, &[0, 128, 255][..]),
+            (FrameFormat::Blue, &[0, 128, 255][..]),
+        ];
+        let image = decode(FrameDecoder::builder().gamma(2.0), &frames, 3, 8);
+        assert_eq!(
+            image.format,
+            DecodedImageFormat::Rgb {
+                bytes_per_channel: 1,
+                gamma: 2.0,
+            }
+        );
+        assert_eq!(image.data, [0, 0, 0, 64, 64, 64, 255, 255, 255]);
+    }
+
+    #[test]
+    fn gamma_is_not_applied_to_black_and_white() {
+        let image = decode(
+            FrameDecoder::builder().gamma(2.0).normalize_to_8bit(true),
+            &[(FrameFormat::Gray, &[0b1010_0000])],
+            8,
+            1,
+        );
+        assert_eq!(
+            image.format,
+            DecodedImageFormat::Gray {
+                bytes_per_pixel: 1,
+                gamma: 1.0,
+            }
+        );
+        assert_eq!(image.data, [0, 255, 0, 255, 255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn format_sizes() {
+        use DecodedImageFormat::*;
+        let cases = [
+            (
+                BlackAndWhite {
+                    packed: true,
+                    white_is_one: true,
+                },
+                None,
+                1,
+            ),
+            (
+                BlackAndWhite {
+                    packed: false,
+                    white_is_one: false,
+                },
+                Some(1),
+                1,
+            ),
+            (
+                Gray {
+                    bytes_per_pixel: 1,
+                    gamma: 1.0,
+                },
+                Some(1),
+                1,
+            ),
+            (
+                Gray {
+                    bytes_per_pixel: 2,
+                    gamma: 1.0,
+                },
+                Some(2),
+                1,
+            ),
+            (
+                Rgb {
+                    bytes_per_channel: 1,
+                    gamma: 1.0,
+                },
+                Some(3),
+                3,
+            ),
+            (
+                Rgb {
+                    bytes_per_channel: 2,
+                    gamma: 1.0,
+                },
+                Some(6),
+                3,
+            ),
+            (
+                Multi {
+                    channels: 4,
+                    bytes_per_channel: 2,
+                },
+                Some(8),
+                4,
+            ),
+        ];
+        for (format, bytes_per_pixel, channels) in cases {
+            assert_eq!(format.bytes_per_pixel(), bytes_per_pixel, "{format:?}");
+            assert_eq!(format.channels(), channels, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn row_stride() {
+        let image = |format, width| DecodedImage {
+            data: Vec::new(),
+            format,
+            width,
+            height: 0,
+        };
+        let packed = DecodedImageFormat::BlackAndWhite {
+            packed: true,
+            white_is_one: true,
+        };
+        assert_eq!(image(packed, 0).row_stride(), 0);
+        assert_eq!(image(packed, 1).row_stride(), 1);
+        assert_eq!(image(packed, 8).row_stride(), 1);
+        assert_eq!(image(packed, 9).row_stride(), 2);
+        let bytes = DecodedImageFormat::BlackAndWhite {
+            packed: false,
+            white_is_one: true,
+        };
+        assert_eq!(image(bytes, 9).row_stride(), 9);
+        let gray16 = DecodedImageFormat::Gray {
+            bytes_per_pixel: 2,
+            gamma: 1.0,
+        };
+        assert_eq!(image(gray16, 5).row_stride(), 10);
+        let rgb16 = DecodedImageFormat::Rgb {
+            bytes_per_channel: 2,
+            gamma: 1.0,
+        };
+        assert_eq!(image(rgb16, 5).row_stride(), 30);
+        let multi = DecodedImageFormat::Multi {
+            channels: 4,
+            bytes_per_channel: 1,
+        };
+        assert_eq!(image(multi, 5).row_stride(), 20);
+    }
+
+    #[test]
+    fn decoded_row_stride_matches_data() {
+        let rgb16: Vec<u8> = (0..2 * 3 * 2 * 2).collect();
+        let image = decode(
+            FrameDecoder::builder(),
+            &[(FrameFormat::Rgb, &rgb16)],
+            2,
+            16,
+        );
+        assert_eq!(image.format.bytes_per_pixel(), Some(6));
+        assert_eq!(image.row_stride() * image.height as usize, image.data.len());
+        assert_eq!(image.data, rgb16);
+    }
+
+    #[test]
+    fn zero_bytes_per_line_is_invalid() {
+        let mut decoder = FrameDecoder::new();
+        let params = FrameParameters::new(FrameFormat::Gray, true, 0, 0, None, 8);
+        assert_eq!(
+            decoder.write(&[], &params),
+            Err(FrameDecodeError::InvalidParameters)
+        );
+        let params = FrameParameters::new(FrameFormat::Gray, true, 0, 4, Some(1), 8);
+        assert_eq!(
+            decoder.write(&[0; 4], &params),
+            Err(FrameDecodeError::InvalidParameters)
+        );
+    }
+
+    #[test]
+    fn image_len_overflow() {
+        assert_eq!(image_len(3, 2, 6), Ok(36));
+        assert_eq!(image_len(u32::MAX, 0, 6), Ok(0));
+        assert_eq!(
+            image_len(u32::MAX, u32::MAX, usize::MAX),
+            Err(FrameDecodeError::InvalidParameters)
+        );
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(
+            image_len(u32::MAX, u32::MAX, 8),
+            Err(FrameDecodeError::InvalidParameters)
+        );
+    }
+
+    #[test]
+    fn near_overflow_dimensions_are_rejected() {
+        let mut decoder = FrameDecoder::new();
+        // the line does not fit into bytes_per_line
+        let params = FrameParameters::new(FrameFormat::Rgb, true, u32::MAX, u32::MAX, None, 16);
+        assert_eq!(
+            decoder.write(&[0; 6], &params),
+            Err(FrameDecodeError::InvalidParameters)
+        );
+        // the frame is shorter than a single line
+        let params = FrameParameters::new(FrameFormat::Gray, true, u32::MAX, u32::MAX, None, 8);
+        assert_eq!(
+            decoder.write(&[0; 6], &params),
+            Err(FrameDecodeError::InvalidParameters)
+        );
+        // absurd parameters must not abort while reserving
+        decoder.reserve_for(&FrameParameters::new(
+            FrameFormat::Rgb,
+            true,
+            u32::MAX,
+            u32::MAX,
+            Some(u32::MAX),
+            16,
+        ));
+        assert!(!decoder.is_done());
+    }
+}
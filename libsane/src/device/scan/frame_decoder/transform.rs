@@ -0,0 +1,434 @@
+use super::{get_bit, set_bit, DecodedImage, DecodedImageFormat};
+
+impl DecodedImage {
+    /// Converts an RGB image to grayscale using the luma weights of ITU-R BT.601
+    /// (`0.299 R + 0.587 G + 0.114 B`). Black and white as well as gray images are
+    /// returned unchanged.
+    ///
+    /// Only 8 and 16 bit channels are converted, as these are the only depths allowed by
+    /// the SANE standard. Other depths are returned unchanged as well.
+    pub fn to_grayscale(&self) -> DecodedImage {
+        let DecodedImageFormat::Rgb {
+            bytes_per_channel,
+            gamma,
+        } = self.format
+        else {
+            return self.clone();
+        };
+        let data = match bytes_per_channel {
+            1 => self
+                .data
+                .chunks_exact(3)
+                .map(|px| luma(px[0].into(), px[1].into(), px[2].into()) as u8)
+                .collect(),
+            2 => self
+                .data
+                .chunks_exact(6)
+                .flat_map(|px| {
+                    // SANE transmits 16 bit samples in native byte order
+                    let [r, g, b] = [0, 2, 4].map(|i| u16::from_ne_bytes([px[i], px[i + 1]]));
+                    (luma(r.into(), g.into(), b.into()) as u16).to_ne_bytes()
+                })
+                .collect(),
+            _ => return self.clone(),
+        };
+        DecodedImage {
+            data,
+            format: DecodedImageFormat::Gray {
+                bytes_per_pixel: bytes_per_channel,
+                gamma,
+            },
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Deinterleaves the pixel data into one buffer per channel, which is one buffer
+    /// for black and white or gray images, three buffers (red, green and blue) for RGB
+    /// images and one buffer per band for multi-band images. Every sample keeps its
+    /// size, so 16 bit samples take up two bytes in native byte order.
+    pub fn split_channels(&self) -> Vec<Vec<u8>> {
+        let channels = self.format.channels() as usize;
+        let bytes_per_pixel = match self.format.bytes_per_pixel() {
+            Some(bytes_per_pixel) if channels > 1 => bytes_per_pixel,
+            _ => return vec![self.data.clone()],
+        };
+        let bytes_per_channel = bytes_per_pixel / channels;
+        let mut split: Vec<Vec<u8>> = (0..channels)
+            .map(|_| Vec::with_capacity(self.data.len() / channels))
+            .collect();
+        if bytes_per_channel == 0 {
+            return split;
+        }
+        for pixel in self.data.chunks_exact(bytes_per_pixel) {
+            for (channel, sample) in split.iter_mut().zip(pixel.chunks_exact(bytes_per_channel)) {
+                channel.extend_from_slice(sample);
+            }
+        }
+        split
+    }
+}
+
+fn luma(r: u32, g: u32, b: u32) -> u32 {
+    (299 * r + 587 * g + 114 * b + 500) / 1000
+}
+
+impl DecodedImage {
+    /// Mirrors the image along the horizontal axis, swapping top and bottom.
+    pub fn flip_vertical(&mut self) {
+        let stride = self.row_stride();
+        if stride == 0 {
+            return;
+        }
+        let height = self.data.len() / stride;
+        for y in 0..height / 2 {
+            let (top, bottom) = self.data.split_at_mut((height - 1 - y) * stride);
+            top[y * stride..(y + 1) * stride].swap_with_slice(&mut bottom[..stride]);
+        }
+    }
+
+    /// Mirrors the image along the vertical axis, swapping left and right.
+    pub fn flip_horizontal(&mut self) {
+        let stride = self.row_stride();
+        if stride == 0 {
+            return;
+        }
+        let width = self.width as usize;
+        match self.format.bytes_per_pixel() {
+            Some(bytes_per_pixel) => {
+                for row in self.data.chunks_exact_mut(stride) {
+                    row.reverse();
+                    // reversing the row also reversed the bytes within each pixel
+                    for pixel in row.chunks_exact_mut(bytes_per_pixel) {
+                        pixel.reverse();
+                    }
+                }
+            }
+            None => {
+                let mut flipped = vec![0; stride];
+                for row in self.data.chunks_exact_mut(stride) {
+                    flipped.fill(0);
+                    for x in 0..width {
+                        set_bit(&mut flipped, width - 1 - x, get_bit(row, x));
+                    }
+                    row.copy_from_slice(&flipped);
+                }
+            }
+        }
+    }
+
+    /// Copies the rectangle at (`x`, `y`) with the given size into a new image.
+    /// Returns `None` if the rectangle is out of bounds.
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Option<DecodedImage> {
+        if x.checked_add(width)? > self.width || y.checked_add(height)? > self.height {
+            return None;
+        }
+        let stride = self.row_stride();
+        let rows = self
+            .data
+            .chunks_exact(stride.max(1))
+            .skip(y as usize)
+            .take(height as usize);
+        let (x, width) = (x as usize, width as usize);
+        let data = match self.format.bytes_per_pixel() {
+            Some(bytes_per_pixel) => rows
+                .flat_map(|row| &row[x * bytes_per_pixel..(x + width) * bytes_per_pixel])
+                .copied()
+                .collect(),
+            None => {
+                let new_stride = width.div_ceil(8);
+                let mut data = vec![0; new_stride * height as usize];
+                for (row, dst) in rows.zip(data.chunks_exact_mut(new_stride.max(1))) {
+                    for i in 0..width {
+                        set_bit(dst, i, get_bit(row, x + i));
+                    }
+                }
+                data
+            }
+        };
+        Some(DecodedImage {
+            data,
+            format: self.format,
+            width: width as u32,
+            height,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Black and white test pattern with a width that is not a multiple of 8.
+    const PATTERN: [&str; 3] = ["#..#.##...#", ".#######.#.", "##.......##"];
+
+    fn pattern_image(packed: bool) -> DecodedImage {
+        image_from_rows(&PATTERN, packed)
+    }
+
+    fn image_from_rows(rows: &[&str], packed: bool) -> DecodedImage {
+        let width = rows.first().map_or(0, |row| row.len());
+        let mut data = Vec::new();
+        for row in rows {
+            if packed {
+                let mut bytes = vec![0; width.div_ceil(8)];
+                for (x, pixel) in row.chars().enumerate() {
+                    set_bit(&mut bytes, x, pixel == '#');
+                }
+                data.extend(bytes);
+            } else {
+                data.extend(row.chars().map(|pixel| (pixel == '#') as u8));
+            }
+        }
+        DecodedImage {
+            data,
+            format: DecodedImageFormat::BlackAndWhite {
+                packed,
+                white_is_one: true,
+            },
+            width: width as u32,
+            height: rows.len() as u32,
+        }
+    }
+
+    fn rows_of(image: &DecodedImage) -> Vec<String> {
+        image
+            .data
+            .chunks_exact(image.row_stride())
+            .map(|row| {
+                (0..image.width as usize)
+                    .map(|x| {
+                        let set = if image.is_packed_bitmap() {
+                            get_bit(row, x)
+                        } else {
+                            row[x] != 0
+                        };
+                        if set {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn crop_black_and_white() {
+        for packed in [true, false] {
+            let image = pattern_image(packed);
+            let cropped = image.crop(2, 1, 7, 2).unwrap();
+            assert_eq!(cropped.format, image.format);
+            assert_eq!((cropped.width, cropped.height), (7, 2));
+            assert_eq!(rows_of(&cropped), ["######.", "......."]);
+            assert_eq!(cropped.data.len(), cropped.row_stride() * 2);
+
+            let single = image.crop(10, 0, 1, 3).unwrap();
+            assert_eq!(rows_of(&single), ["#", ".", "#"]);
+            assert_eq!(rows_of(&image.crop(0, 0, 11, 3).unwrap()), PATTERN);
+            assert!(image.crop(5, 0, 7, 1).is_none());
+            assert!(image.crop(0, 2, 1, 2).is_none());
+        }
+    }
+
+    #[test]
+    fn crop_packed_width_one() {
+        let image = image_from_rows(&["#", "."], true);
+        assert_eq!(image.data, [0x80, 0x00]);
+        assert_eq!(image.row_stride(), 1);
+        assert!(image.is_packed_bitmap());
+        let cropped = image.crop(0, 1, 1, 1).unwrap();
+        assert_eq!(cropped.data, [0x00]);
+        assert!(cropped.is_packed_bitmap());
+    }
+
+    #[test]
+    fn flip_black_and_white() {
+        for packed in [true, false] {
+            let mut image = pattern_image(packed);
+            image.flip_horizontal();
+            assert_eq!(
+                rows_of(&image),
+                ["#...##.#..#", ".#.#######.", "##.......##"]
+            );
+            image.flip_vertical();
+            assert_eq!(
+                rows_of(&image),
+                ["##.......##", ".#.#######.", "#...##.#..#"]
+            );
+            image.flip_horizontal();
+            image.flip_vertical();
+            assert_eq!(rows_of(&image), PATTERN);
+        }
+    }
+
+    #[test]
+    fn crop_and_flip_rgb() {
+        let mut image = DecodedImage {
+            data: (0..2 * 3 * 3).collect(),
+            format: DecodedImageFormat::Rgb {
+                bytes_per_channel: 1,
+                gamma: 1.0,
+            },
+            width: 3,
+            height: 2,
+        };
+        let cropped = image.crop(1, 1, 2, 1).unwrap();
+        assert_eq!(cropped.data, [12, 13, 14, 15, 16, 17]);
+        image.flip_horizontal();
+        assert_eq!(
+            image.data,
+            [6, 7, 8, 3, 4, 5, 0, 1, 2, 15, 16, 17, 12, 13, 14, 9, 10, 11]
+        );
+    }
+
+    #[test]
+    fn to_grayscale_8bit() {
+        // black, white, red, green, blue and a mid gray
+        let swatch = [
+            [0, 0, 0],
+            [255, 255, 255],
+            [255, 0, 0],
+            [0, 255, 0],
+            [0, 0, 255],
+            [128, 128, 128],
+        ];
+        let image = DecodedImage {
+            data: swatch.concat(),
+            format: DecodedImageFormat::Rgb {
+                bytes_per_channel: 1,
+                gamma: 2.2,
+            },
+            width: 3,
+            height: 2,
+        };
+        let gray = image.to_grayscale();
+        assert_eq!(
+            gray.format,
+            DecodedImageFormat::Gray {
+                bytes_per_pixel: 1,
+                gamma: 2.2,
+            }
+        );
+        assert_eq!((gray.width, gray.height), (3, 2));
+        assert_eq!(gray.data, [0, 255, 76, 150, 29, 128]);
+    }
+
+    #[test]
+    fn to_grayscale_16bit() {
+        let swatch = [
+            [0, 0, 0],
+            [u16::MAX, u16::MAX, u16::MAX],
+            [u16::MAX, 0, 0],
+            [0, u16::MAX, 0],
+            [0, 0, u16::MAX],
+        ];
+        let image = DecodedImage {
+            data: swatch
+                .iter()
+                .flatten()
+                .flat_map(|sample| sample.to_ne_bytes())
+                .collect(),
+            format: DecodedImageFormat::Rgb {
+                bytes_per_channel: 2,
+                gamma: 1.0,
+            },
+            width: 5,
+            height: 1,
+        };
+        let gray = image.to_grayscale();
+        assert_eq!(gray.format.bytes_per_pixel(), Some(2));
+        let samples: Vec<u16> = gray
+            .data
+            .chunks_exact(2)
+            .map(|sample| u16::from_ne_bytes([sample[0], sample[1]]))
+            .collect();
+        assert_eq!(samples, [0, u16::MAX, 19595, 38469, 7471]);
+    }
+
+    #[test]
+    fn to_grayscale_keeps_other_formats() {
+        let image = pattern_image(true);
+        let gray = image.to_grayscale();
+        assert_eq!(gray.format, image.format);
+        assert_eq!(gray.data, image.data);
+    }
+
+    /// Interleaves the channels again, the inverse of [`DecodedImage::split_channels`].
+    fn interleave(channels: &[Vec<u8>], bytes_per_channel: usize) -> Vec<u8> {
+        let pixels = channels[0].len() / bytes_per_channel;
+        (0..pixels)
+            .flat_map(|i| {
+                channels
+                    .iter()
+                    .flat_map(move |c| &c[i * bytes_per_channel..(i + 1) * bytes_per_channel])
+            })
+            .copied()
+            .collect()
+    }
+
+    #[test]
+    fn split_channels_round_trip() {
+        let cases = [
+            (
+                DecodedImageFormat::Rgb {
+                    bytes_per_channel: 1,
+                    gamma: 1.0,
+                },
+                1,
+            ),
+            (
+                DecodedImageFormat::Rgb {
+                    bytes_per_channel: 2,
+                    gamma: 1.0,
+                },
+                2,
+            ),
+            (
+                DecodedImageFormat::Multi {
+                    channels: 4,
+                    bytes_per_channel: 2,
+                },
+                2,
+            ),
+        ];
+        for (format, bytes_per_channel) in cases {
+            let channels = format.channels() as usize;
+            let image = DecodedImage {
+                data: (0..format.bytes_per_pixel().unwrap() * 6)
+                    .map(|i| i as u8)
+                    .collect(),
+                format,
+                width: 3,
+                height: 2,
+            };
+            let split = image.split_channels();
+            assert_eq!(split.len(), channels);
+            for channel in &split {
+                assert_eq!(channel.len(), 6 * bytes_per_channel);
+            }
+            assert_eq!(
+                split[0][..bytes_per_channel],
+                image.data[..bytes_per_channel]
+            );
+            assert_eq!(interleave(&split, bytes_per_channel), image.data);
+        }
+    }
+
+    #[test]
+    fn split_channels_single_channel() {
+        let image = pattern_image(true);
+        assert_eq!(image.split_channels(), std::slice::from_ref(&image.data));
+        let gray = DecodedImage {
+            data: vec![1, 2, 3],
+            format: DecodedImageFormat::Gray {
+                bytes_per_pixel: 1,
+                gamma: 1.0,
+            },
+            width: 3,
+            height: 1,
+        };
+        assert_eq!(gray.split_channels(), [vec![1, 2, 3]]);
+    }
+}
@@ -12,6 +12,32 @@ pub struct Error {
 }
 
 impl Error {
+    /// Creates an error from the given status without checking it. The status
+    /// must not be [`Good`][`sys::Status::Good`], use [`Self::from_status`]
+    /// if it may be.
+    ///
+    /// This is mostly useful to create synthetic errors, for example in tests.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug builds if the status is [`Good`][`sys::Status::Good`].
+    pub const fn from_sys_status(status: sys::Status) -> Self {
+        debug_assert!(
+            !matches!(status, sys::Status::Good),
+            "an error cannot have the status Good"
+        );
+        Self { status }
+    }
+
+    /// Creates an error from the given status, or returns `None` if the status is
+    /// [`Good`][`sys::Status::Good`].
+    pub const fn from_status(status: sys::Status) -> Option<Self> {
+        match status {
+            sys::Status::Good => None,
+            status => Some(Self { status }),
+        }
+    }
+
     pub const fn status(&self) -> Status {
         Status::from_sys(self.status)
     }
@@ -51,10 +77,12 @@ impl Display for Error {
 
 impl StdError for Error {}
 
-pub(crate) fn status_result(status: sys::Status) -> Result<(), Error> {
-    match status {
-        sys::Status::Good => Ok(()),
-        status => Err(Error { status }),
+/// Converts a status returned by a SANE function into a [`Result`]. This can be
+/// used to wrap calls into [`sys`] which are not covered by this crate.
+pub const fn status_result(status: sys::Status) -> Result<(), Error> {
+    match Error::from_status(status) {
+        None => Ok(()),
+        Some(error) => Err(error),
     }
 }
 
@@ -91,6 +119,32 @@ impl Status {
             _ => Self::Unknown,
         }
     }
+
+    /// English description of this status, matching the messages of `sane_strstatus` in
+    /// `sane-backends`. Unlike [`Error::message`], this does not call into the library,
+    /// so it also works for synthetic statuses.
+    pub const fn message(&self) -> &'static str {
+        match self {
+            Self::Unsupported => "Operation not supported",
+            Self::Cancelled => "Operation was canceled",
+            Self::DeviceBusy => "Device busy",
+            Self::Inval => "Invalid argument",
+            Self::Eof => "End of file reached",
+            Self::Jammed => "Document feeder jammed",
+            Self::NoDocs => "Document feeder out of documents",
+            Self::CoverOpen => "Scanner cover is open",
+            Self::IoError => "Error during device I/O",
+            Self::NoMem => "Out of memory",
+            Self::AccessDenied => "Access to resource has been denied",
+            Self::Unknown => "Unknown SANE status code",
+        }
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
 }
 
 impl From<sys::Status> for Status {
@@ -98,3 +152,24 @@ impl From<sys::Status> for Status {
         Self::from_sys(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_status_rejects_good() {
+        assert_eq!(Error::from_status(sys::Status::Good), None);
+        assert_eq!(
+            Error::from_status(sys::Status::Inval),
+            Some(Error::from_sys_status(sys::Status::Inval))
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn from_sys_status_rejects_good() {
+        Error::from_sys_status(sys::Status::Good);
+    }
+}
@@ -14,6 +14,34 @@ impl Fixed {
         Self(sys::fix(v))
     }
 
+    /// Creates a length in millimeters, the [unit][`crate::Unit::Millimeter`] of
+    /// geometry options such as the scan area. Same as [`Self::new`].
+    #[inline]
+    pub fn from_mm(mm: f64) -> Self {
+        Self::new(mm)
+    }
+
+    /// Converts a length to millimeters, the [unit][`crate::Unit::Millimeter`] of
+    /// geometry options such as the scan area.
+    #[inline]
+    pub fn as_mm(self) -> f64 {
+        f64::from(self)
+    }
+
+    /// Creates a resolution in dots per inch, the [unit][`crate::Unit::Dpi`] of
+    /// resolution options. Same as [`Self::new`].
+    #[inline]
+    pub fn from_dpi(dpi: f64) -> Self {
+        Self::new(dpi)
+    }
+
+    /// Converts a resolution to dots per inch, the [unit][`crate::Unit::Dpi`] of
+    /// resolution options.
+    #[inline]
+    pub fn as_dpi(self) -> f64 {
+        f64::from(self)
+    }
+
     pub const fn from_bits(bits: sys::Fixed) -> Self {
         Self(bits)
     }
@@ -21,6 +49,38 @@ impl Fixed {
     pub const fn to_bits(self) -> sys::Fixed {
         self.0
     }
+
+    /// Adds `rhs`, returning `None` on overflow.
+    pub const fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.0.checked_add(rhs.0) {
+            Some(bits) => Some(Self(bits)),
+            None => None,
+        }
+    }
+
+    /// Subtracts `rhs`, returning `None` on overflow.
+    pub const fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.0.checked_sub(rhs.0) {
+            Some(bits) => Some(Self(bits)),
+            None => None,
+        }
+    }
+
+    /// Adds `rhs`, clamping the result to the representable range.
+    pub const fn saturating_add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+
+    /// Subtracts `rhs`, clamping the result to the representable range.
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+
+    /// Computes the absolute value. Like for `i32`, this overflows for the smallest
+    /// representable value.
+    pub const fn abs(self) -> Self {
+        Self(self.0.abs())
+    }
 }
 
 impl From<f64> for Fixed {
@@ -35,6 +95,13 @@ impl From<Fixed> for f64 {
     }
 }
 
+/// Formats the value like an [`f64`], honoring the width, precision and alignment of
+/// the format string, for example to print option values in a table:
+///
+/// ```
+/// # use libsane::Fixed;
+/// assert_eq!(format!("{:>8.1}|{:.2}", Fixed::new(3.14159), Fixed::new(0.5)), "     3.1|0.50");
+/// ```
 impl fmt::Display for Fixed {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(&f64::from(*self), f)
@@ -63,6 +130,16 @@ impl ops::Sub for Fixed {
     }
 }
 
+impl ops::Neg for Fixed {
+    type Output = Self;
+
+    /// Negates the value. Like for `i32`, this overflows for the smallest
+    /// representable value.
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
 impl ops::AddAssign for Fixed {
     fn add_assign(&mut self, rhs: Self) {
         self.0 += rhs.0
@@ -74,3 +151,80 @@ impl ops::SubAssign for Fixed {
         self.0 -= rhs.0
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Fixed {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(f64::from(*self))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Fixed {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX: Fixed = Fixed::from_bits(i32::MAX);
+    const MIN: Fixed = Fixed::from_bits(i32::MIN);
+    const EPS: Fixed = Fixed::from_bits(1);
+
+    #[test]
+    fn checked_at_bounds() {
+        assert_eq!(MAX.checked_add(EPS), None);
+        assert_eq!(MAX.checked_add(Fixed::from_bits(0)), Some(MAX));
+        assert_eq!(MIN.checked_sub(EPS), None);
+        assert_eq!(MIN.checked_add(EPS), Some(Fixed::from_bits(i32::MIN + 1)));
+        assert_eq!(MAX.checked_sub(EPS), Some(Fixed::from_bits(i32::MAX - 1)));
+        assert_eq!(MIN.checked_add(MAX), Some(-EPS));
+        assert_eq!(Fixed::from_bits(0).checked_sub(MIN), None);
+        assert_eq!(
+            Fixed::new(1.5).checked_add(Fixed::new(2.0)),
+            Some(Fixed::new(3.5))
+        );
+    }
+
+    #[test]
+    fn saturating_at_bounds() {
+        assert_eq!(MAX.saturating_add(EPS), MAX);
+        assert_eq!(MAX.saturating_add(MAX), MAX);
+        assert_eq!(MIN.saturating_sub(EPS), MIN);
+        assert_eq!(MIN.saturating_add(MIN), MIN);
+        assert_eq!(Fixed::from_bits(0).saturating_sub(MIN), MAX);
+        assert_eq!(MAX.saturating_sub(EPS), Fixed::from_bits(i32::MAX - 1));
+        assert_eq!(
+            Fixed::new(1.5).saturating_sub(Fixed::new(2.0)),
+            Fixed::new(-0.5)
+        );
+    }
+
+    #[test]
+    fn neg_and_abs_at_bounds() {
+        assert_eq!(-MAX, Fixed::from_bits(-i32::MAX));
+        assert_eq!(-(-MAX), MAX);
+        assert_eq!(-Fixed::from_bits(i32::MIN + 1), MAX);
+        assert_eq!(-Fixed::new(1.5), Fixed::new(-1.5));
+        assert_eq!(MAX.abs(), MAX);
+        assert_eq!(Fixed::from_bits(i32::MIN + 1).abs(), MAX);
+        assert_eq!(Fixed::new(-2.25).abs(), Fixed::new(2.25));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn neg_overflows_at_min() {
+        let _ = -std::hint::black_box(MIN);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn abs_overflows_at_min() {
+        let _ = std::hint::black_box(MIN).abs();
+    }
+}
@@ -0,0 +1,3 @@
+//! Encoders for [decoded images][`crate::scan::DecodedImage`].
+
+pub mod pnm;
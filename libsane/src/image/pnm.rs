@@ -0,0 +1,378 @@
+//! Writers for the uncompressed [Netpbm] image formats.
+//!
+//! [Netpbm]: https://netpbm.sourceforge.net/doc/
+
+use std::io::{self, Write};
+
+use crate::scan::{
+    frame_decoder::{get_bit, set_bit},
+    DecodedImage, DecodedImageFormat,
+};
+
+/// Writes the image in the [PAM] format, which supports all image formats.
+///
+/// [PAM]: https://netpbm.sourceforge.net/doc/pam.html
+pub fn write_pam(image: &DecodedImage, w: &mut impl Write) -> io::Result<()> {
+    let (depth, tupltype, maxval) = match image.format {
+        DecodedImageFormat::BlackAndWhite { .. } => (1, Some("BLACKANDWHITE"), 1),
+        DecodedImageFormat::Gray {
+            bytes_per_pixel, ..
+        } => (1, Some("GRAYSCALE"), maxval(bytes_per_pixel)?),
+        DecodedImageFormat::Rgb {
+            bytes_per_channel, ..
+        } => (3, Some("RGB"), maxval(bytes_per_channel)?),
+        // PAM does not define a tuple type for arbitrary bands, so it is omitted
+        DecodedImageFormat::Multi {
+            channels,
+            bytes_per_channel,
+        } => (channels as u32, None, maxval(bytes_per_channel)?),
+    };
+    write!(
+        w,
+        "P7\nWIDTH {}\nHEIGHT {}\nDEPTH {depth}\nMAXVAL {maxval}\n",
+        image.width, image.height,
+    )?;
+    if let Some(tupltype) = tupltype {
+        writeln!(w, "TUPLTYPE {tupltype}")?;
+    }
+    w.write_all(b"ENDHDR\n")?;
+    match image.format {
+        DecodedImageFormat::BlackAndWhite {
+            packed: false,
+            white_is_one: true,
+        } => w.write_all(&image.data),
+        DecodedImageFormat::BlackAndWhite { white_is_one, .. } => {
+            // PAM stores every pixel in its own byte, and 1 is white
+            let width = image.width as usize;
+            for row in image.data.chunks_exact(image.row_stride().max(1)) {
+                let samples: Vec<u8> = (0..width)
+                    .map(|x| (pixel_bit(image, row, x) == white_is_one) as u8)
+                    .collect();
+                w.write_all(&samples)?;
+            }
+            Ok(())
+        }
+        _ => write_samples(image, w),
+    }
+}
+
+/// Writes a gray image in the [PGM] format.
+///
+/// [PGM]: https://netpbm.sourceforge.net/doc/pgm.html
+pub fn write_pgm(image: &DecodedImage, w: &mut impl Write) -> io::Result<()> {
+    let DecodedImageFormat::Gray {
+        bytes_per_pixel, ..
+    } = image.format
+    else {
+        return Err(unsupported_format());
+    };
+    let maxval = maxval(bytes_per_pixel)?;
+    write!(w, "P5\n{} {}\n{maxval}\n", image.width, image.height)?;
+    write_samples(image, w)
+}
+
+/// Writes an RGB image in the [PPM] format.
+///
+/// [PPM]: https://netpbm.sourceforge.net/doc/ppm.html
+pub fn write_ppm(image: &DecodedImage, w: &mut impl Write) -> io::Result<()> {
+    let DecodedImageFormat::Rgb {
+        bytes_per_channel, ..
+    } = image.format
+    else {
+        return Err(unsupported_format());
+    };
+    let maxval = maxval(bytes_per_channel)?;
+    write!(w, "P6\n{} {}\n{maxval}\n", image.width, image.height)?;
+    write_samples(image, w)
+}
+
+/// Writes a black and white image in the [PBM] format.
+///
+/// [PBM]: https://netpbm.sourceforge.net/doc/pbm.html
+pub fn write_pbm(image: &DecodedImage, w: &mut impl Write) -> io::Result<()> {
+    let DecodedImageFormat::BlackAndWhite { white_is_one, .. } = image.format else {
+        return Err(unsupported_format());
+    };
+    write!(w, "P4\n{} {}\n", image.width, image.height)?;
+    let width = image.width as usize;
+    let mut packed = vec![0; width.div_ceil(8)];
+    for row in image.data.chunks_exact(image.row_stride().max(1)) {
+        // In PBM, 1 is black
+        if image.is_packed_bitmap() {
+            let mask = if white_is_one { u8::MAX } else { 0 };
+            for (dst, src) in packed.iter_mut().zip(row) {
+                *dst = *src ^ mask;
+            }
+            // clear the padding bits, which may have been set by the inversion
+            if !width.is_multiple_of(8) {
+                packed[width / 8] &= !(0xFF >> (width % 8));
+            }
+        } else {
+            for x in 0..width {
+                set_bit(&mut packed, x, pixel_bit(image, row, x) != white_is_one);
+            }
+        }
+        w.write_all(&packed)?;
+    }
+    Ok(())
+}
+
+/// Reads the pixel at `x` of a row of a black and white image, which is either packed or
+/// stored as bytes.
+fn pixel_bit(image: &DecodedImage, row: &[u8], x: usize) -> bool {
+    if image.is_packed_bitmap() {
+        get_bit(row, x)
+    } else {
+        row[x] != 0
+    }
+}
+
+/// Writes the samples of a gray, RGB or multi-band image, which are big-endian in Netpbm formats.
+fn write_samples(image: &DecodedImage, w: &mut impl Write) -> io::Result<()> {
+    match image.format {
+        DecodedImageFormat::Gray {
+            bytes_per_pixel: 2, ..
+        }
+        | DecodedImageFormat::Rgb {
+            bytes_per_channel: 2,
+            ..
+        }
+        | DecodedImageFormat::Multi {
+            bytes_per_channel: 2,
+            ..
+        } => {
+            let samples: Vec<u8> = image
+                .data
+                .chunks_exact(2)
+                // SANE transmits 16 bit samples in native byte order
+                .flat_map(|sample| u16::from_ne_bytes([sample[0], sample[1]]).to_be_bytes())
+                .collect();
+            w.write_all(&samples)
+        }
+        _ => w.write_all(&image.data),
+    }
+}
+
+fn maxval(bytes_per_sample: u32) -> io::Result<u32> {
+    match bytes_per_sample {
+        1 => Ok(u8::MAX.into()),
+        2 => Ok(u16::MAX.into()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Netpbm formats support at most 16 bits per sample",
+        )),
+    }
+}
+
+fn unsupported_format() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "image format is not supported by this Netpbm format",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{FrameDecoder, FrameFormat, FrameParameters};
+
+    fn image(format: DecodedImageFormat, width: u32, height: u32, data: &[u8]) -> DecodedImage {
+        DecodedImage {
+            data: data.to_vec(),
+            format,
+            width,
+            height,
+        }
+    }
+
+    fn written(
+        write: fn(&DecodedImage, &mut Vec<u8>) -> io::Result<()>,
+        image: &DecodedImage,
+    ) -> Vec<u8> {
+        let mut out = Vec::new();
+        write(image, &mut out).unwrap();
+        out
+    }
+
+    fn gray16() -> DecodedImage {
+        let data: Vec<u8> = [0x0102u16, 0xFFFE]
+            .iter()
+            .flat_map(|sample| sample.to_ne_bytes())
+            .collect();
+        let format = DecodedImageFormat::Gray {
+            bytes_per_pixel: 2,
+            gamma: 1.0,
+        };
+        image(format, 2, 1, &data)
+    }
+
+    #[test]
+    fn pgm() {
+        let format = DecodedImageFormat::Gray {
+            bytes_per_pixel: 1,
+            gamma: 1.0,
+        };
+        let out = written(write_pgm, &image(format, 2, 2, &[0, 64, 128, 255]));
+        assert_eq!(out, b"P5\n2 2\n255\n\x00\x40\x80\xFF");
+
+        let out = written(write_pgm, &gray16());
+        assert_eq!(out, b"P5\n2 1\n65535\n\x01\x02\xFF\xFE");
+    }
+
+    #[test]
+    fn ppm() {
+        let format = DecodedImageFormat::Rgb {
+            bytes_per_channel: 1,
+            gamma: 1.0,
+        };
+        let out = written(write_ppm, &image(format, 1, 1, &[1, 2, 3]));
+        assert_eq!(out, b"P6\n1 1\n255\n\x01\x02\x03");
+
+        let data: Vec<u8> = [0x0102u16, 0x0304, 0x0506]
+            .iter()
+            .flat_map(|sample| sample.to_ne_bytes())
+            .collect();
+        let format = DecodedImageFormat::Rgb {
+            bytes_per_channel: 2,
+            gamma: 1.0,
+        };
+        let out = written(write_ppm, &image(format, 1, 1, &data));
+        assert_eq!(out, b"P6\n1 1\n65535\n\x01\x02\x03\x04\x05\x06");
+    }
+
+    #[test]
+    fn pbm() {
+        // 10 pixels per row, 1 is white: "#.##......" and ".........#"
+        let packed = image(
+            DecodedImageFormat::BlackAndWhite {
+                packed: true,
+                white_is_one: true,
+            },
+            10,
+            2,
+            &[0b0100_1111, 0b1100_0000, 0b1111_1111, 0b1000_0000],
+        );
+        let bytes = image(
+            DecodedImageFormat::BlackAndWhite {
+                packed: false,
+                white_is_one: true,
+            },
+            10,
+            2,
+            &[
+                0, 1, 0, 0, 1, 1, 1, 1, 1, 1, //
+                1, 1, 1, 1, 1, 1, 1, 1, 1, 0,
+            ],
+        );
+        for image in [packed, bytes] {
+            let out = written(write_pbm, &image);
+            assert_eq!(out, b"P4\n10 2\n\xB0\x00\x00\x40");
+        }
+    }
+
+    #[test]
+    fn pam() {
+        let out = written(write_pam, &gray16());
+        assert_eq!(
+            out,
+            b"P7\nWIDTH 2\nHEIGHT 1\nDEPTH 1\nMAXVAL 65535\nTUPLTYPE GRAYSCALE\nENDHDR\n\x01\x02\xFF\xFE"
+        );
+
+        let header = b"P7\nWIDTH 10\nHEIGHT 1\nDEPTH 1\nMAXVAL 1\nTUPLTYPE BLACKANDWHITE\nENDHDR\n";
+        let samples = [0, 1, 0, 0, 1, 1, 1, 1, 1, 1];
+        let packed = image(
+            DecodedImageFormat::BlackAndWhite {
+                packed: true,
+                white_is_one: true,
+            },
+            10,
+            1,
+            &[0b0100_1111, 0b1100_0000],
+        );
+        let bytes = image(
+            DecodedImageFormat::BlackAndWhite {
+                packed: false,
+                white_is_one: true,
+            },
+            10,
+            1,
+            &samples,
+        );
+        for image in [packed, bytes] {
+            assert_eq!(written(write_pam, &image), [&header[..], &samples].concat());
+        }
+
+        let format = DecodedImageFormat::Multi {
+            channels: 4,
+            bytes_per_channel: 1,
+        };
+        let out = written(write_pam, &image(format, 1, 1, &[1, 2, 3, 4]));
+        assert_eq!(
+            out,
+            b"P7\nWIDTH 1\nHEIGHT 1\nDEPTH 4\nMAXVAL 255\nENDHDR\n\x01\x02\x03\x04"
+        );
+    }
+
+    #[test]
+    fn packed_width_one() {
+        let image = image(
+            DecodedImageFormat::BlackAndWhite {
+                packed: true,
+                white_is_one: true,
+            },
+            1,
+            2,
+            &[0x80, 0x00],
+        );
+        let out = written(write_pam, &image);
+        assert!(out.ends_with(b"MAXVAL 1\nTUPLTYPE BLACKANDWHITE\nENDHDR\n\x01\x00"));
+        let out = written(write_pbm, &image);
+        assert_eq!(out[out.len() - 2..], [0x00, 0x80]);
+    }
+
+    #[test]
+    fn black_and_white_polarity_round_trip() {
+        // SANE uses 1 for black, like PBM: "#.#....."
+        let frame = [0b1010_0000];
+        let params = FrameParameters::new(FrameFormat::Gray, true, 1, 8, Some(1), 1);
+        for invert in [true, false] {
+            for as_bytes in [true, false] {
+                let mut decoder = FrameDecoder::builder()
+                    .invert_lineart(invert)
+                    .decode_black_and_white_as_bytes(as_bytes)
+                    .build();
+                decoder.write(&frame, &params).unwrap();
+                let image = decoder.into_image().unwrap();
+                assert_eq!(
+                    image.format,
+                    DecodedImageFormat::BlackAndWhite {
+                        packed: !as_bytes,
+                        white_is_one: invert,
+                    }
+                );
+
+                assert_eq!(written(write_pbm, &image), b"P4\n8 1\n\xA0");
+                let out = written(write_pam, &image);
+                assert!(out.ends_with(b"ENDHDR\n\x00\x01\x00\x01\x01\x01\x01\x01"));
+            }
+        }
+    }
+
+    #[test]
+    fn unsupported_formats() {
+        let mut out = Vec::new();
+        assert!(write_ppm(&gray16(), &mut out).is_err());
+        assert!(write_pbm(&gray16(), &mut out).is_err());
+        let format = DecodedImageFormat::Rgb {
+            bytes_per_channel: 1,
+            gamma: 1.0,
+        };
+        assert!(write_pgm(&image(format, 0, 0, &[]), &mut out).is_err());
+        let format = DecodedImageFormat::Gray {
+            bytes_per_pixel: 4,
+            gamma: 1.0,
+        };
+        assert!(write_pgm(&image(format, 0, 0, &[]), &mut out).is_err());
+        assert!(out.is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use core::fmt;
 use std::{
     cell::UnsafeCell,
+    env,
     error::Error as StdError,
     marker::PhantomData,
     mem::MaybeUninit,
@@ -10,6 +11,9 @@ use std::{
 use crate::{error, slice_util::slice_as_maybe_uninit, sys, Error, Sane, SaneStr, Version};
 
 static HAS_INSTANCE: AtomicBool = AtomicBool::new(false);
+/// Held by tests which initialize [`Sane`], as it can only be initialized once at a time.
+#[cfg(test)]
+pub(crate) static TEST_INSTANCE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
 static STATIC_SYNC_DATA: StaticSyncData = StaticSyncData {
     auth_handler: UnsafeCell::new(None),
 };
@@ -64,6 +68,34 @@ impl Authorizer<'_> {
         Ok(AuthOk(()))
     }
 
+    /// Provides the credentials stored in the environment variables `user_var`
+    /// and `pass_var`, which is useful for unattended access to network scanners.
+    ///
+    /// # Errors
+    /// Returns [`AuthFieldError::Missing`] if a variable is not set or not valid
+    /// Unicode, otherwise see [`Self::provide_credentials`].
+    pub fn provide_from_env(
+        &mut self,
+        user_var: &str,
+        pass_var: &str,
+    ) -> Result<AuthOk, AuthError> {
+        let username =
+            env::var(user_var).map_err(|_| AuthError::Username(AuthFieldError::Missing))?;
+        let password =
+            env::var(pass_var).map_err(|_| AuthError::Password(AuthFieldError::Missing))?;
+        self.provide_credentials(&username, &password)
+    }
+
+    /// Declines the authorization request by providing empty credentials, for
+    /// example when the user closed the login prompt. This typically results in
+    /// an [`AccessDenied`][`crate::error::Status::AccessDenied`] error from the
+    /// pending call.
+    pub fn decline(self) -> AuthOk {
+        self.username[0] = MaybeUninit::new(0);
+        self.password[0] = MaybeUninit::new(0);
+        AuthOk(())
+    }
+
     fn write_str(target: &mut [MaybeUninit<u8>], source: &str) -> Result<(), AuthFieldError> {
         let mut target_iter = target.iter_mut();
         for (dest, ch) in (&mut target_iter).zip(source.chars()) {
@@ -91,9 +123,11 @@ impl Authorizer<'_> {
 }
 
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum AuthFieldError {
     NotLatin1,
     TooLong,
+    Missing,
 }
 
 impl fmt::Display for AuthFieldError {
@@ -101,6 +135,7 @@ impl fmt::Display for AuthFieldError {
         let msg = match self {
             Self::NotLatin1 => "field contains non-Latin1 characters",
             Self::TooLong => "field is too long",
+            Self::Missing => "field is missing",
         };
         f.write_str(msg)
     }
@@ -158,8 +193,40 @@ impl<A> Drop for Sane<A> {
     }
 }
 
-impl<A> Sane<A> {
-    pub fn init(authorize: Option<Box<A>>) -> Result<(Self, Version), Error>
+/// Builder for initializing [`Sane`], obtained from [`Sane::builder`].
+///
+/// The type of the authorization handler is tracked by `A`, which is
+/// [`NoAuth`] until a handler is set using [`Self::auth`].
+pub struct SaneBuilder<A> {
+    auth: Option<Box<A>>,
+}
+
+impl Default for SaneBuilder<NoAuth> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SaneBuilder<NoAuth> {
+    pub const fn new() -> Self {
+        Self { auth: None }
+    }
+}
+
+impl<A> SaneBuilder<A> {
+    /// Sets the handler that is called when a backend requires credentials to
+    /// access a resource.
+    pub fn auth<B: AuthorizationCallback + 'static>(self, callback: B) -> SaneBuilder<B> {
+        SaneBuilder {
+            auth: Some(Box::new(callback)),
+        }
+    }
+
+    /// Initializes Sane.
+    ///
+    /// # Panics
+    /// Sane must only be initialized once at a time.
+    pub fn build(self) -> Result<(Sane<A>, Version), Error>
     where
         A: AuthorizationCallback + 'static,
     {
@@ -169,7 +236,7 @@ impl<A> Sane<A> {
 
         assert!(!has_instance, "Sane has already been initialized once");
 
-        if let Some(authorize) = authorize {
+        if let Some(authorize) = self.auth {
             // SAFETY: Only written to directly before sane_init, and locked
             //         by HAS_INSTANCE, therefore no other accesses.
             let ah = unsafe { &mut *STATIC_SYNC_DATA.auth_handler.get() };
@@ -185,6 +252,7 @@ impl<A> Sane<A> {
             (
                 Sane {
                     _phant: PhantomData,
+                    version,
                 },
                 version,
             )
@@ -202,6 +270,16 @@ impl<A> Sane<A> {
     }
 }
 
+impl<A> Sane<A> {
+    #[inline]
+    pub fn init(authorize: Option<Box<A>>) -> Result<(Self, Version), Error>
+    where
+        A: AuthorizationCallback + 'static,
+    {
+        SaneBuilder { auth: authorize }.build()
+    }
+}
+
 unsafe extern "C" fn authorize_callback(
     resource: sys::StringConst,
     username: *mut sys::Char,
@@ -244,8 +322,12 @@ impl AuthorizationCallback for NoAuth {
 }
 
 impl Sane<NoAuth> {
+    pub const fn builder() -> SaneBuilder<NoAuth> {
+        SaneBuilder::new()
+    }
+
     #[inline]
     pub fn init_no_auth() -> Result<(Self, Version), Error> {
-        Self::init(None)
+        Self::builder().build()
     }
 }
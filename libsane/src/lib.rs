@@ -1,6 +1,8 @@
 mod device;
 mod error;
 mod fixed;
+#[cfg(feature = "pnm")]
+pub mod image;
 mod init_exit;
 pub mod list;
 mod proxied_sys;
@@ -13,7 +15,7 @@ use std::{cell::Cell, marker::PhantomData};
 
 pub use ::libsane_sys as sys;
 pub use device::*;
-pub use error::Error;
+pub use error::{status_result, Error};
 pub use fixed::Fixed;
 pub use init_exit::*;
 pub use string::{SaneStr, SaneString};
@@ -34,6 +36,14 @@ const fn sys_bool(v: bool) -> sys::Bool {
 pub struct Sane<A> {
     /// Sane is !Sync and Send iff A is Send
     _phant: PhantomData<Cell<A>>,
+    version: Version,
+}
+
+impl<A> Sane<A> {
+    /// Version of the SANE backend, as negotiated during initialization.
+    pub const fn version(&self) -> Version {
+        self.version
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -150,11 +160,55 @@ impl<T: WithSane> WithSane for parking_lot::MutexGuard<'_, T> {
     }
 }
 
+/// SANE requires exclusive access, therefore the write lock is taken.
+/// A read lock is insufficient, as the SANE API is not reentrant.
+#[cfg(feature = "parking_lot")]
+impl<T: WithSane> WithSane for parking_lot::RwLock<T> {
+    type Auth = T::Auth;
+
+    fn with_sane<R>(&self, cb: impl for<'a> FnOnce(&'a Sane<Self::Auth>) -> R) -> R {
+        self.write().with_sane(cb)
+    }
+}
+
+#[cfg(feature = "parking_lot")]
+impl<T: WithSane> WithSane for parking_lot::RwLockWriteGuard<'_, T> {
+    type Auth = T::Auth;
+
+    fn with_sane<R>(&self, cb: impl for<'b> FnOnce(&'b Sane<Self::Auth>) -> R) -> R {
+        (**self).with_sane(cb)
+    }
+}
+
+/// Uses [`tokio::sync::Mutex::blocking_lock`], therefore this must not be called from
+/// within an asynchronous execution context. Use `tokio::task::spawn_blocking` instead.
+#[cfg(feature = "tokio")]
+impl<T: WithSane> WithSane for tokio::sync::Mutex<T> {
+    type Auth = T::Auth;
+
+    fn with_sane<R>(&self, cb: impl for<'a> FnOnce(&'a Sane<Self::Auth>) -> R) -> R {
+        self.blocking_lock().with_sane(cb)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T: WithSane> WithSane for tokio::sync::MutexGuard<'_, T> {
+    type Auth = T::Auth;
+
+    fn with_sane<R>(&self, cb: impl for<'b> FnOnce(&'b Sane<Self::Auth>) -> R) -> R {
+        (**self).with_sane(cb)
+    }
+}
+
+/// A poisoned Mutex is recovered, as a panic while holding the lock does not
+/// leave SANE in an invalid state.
 impl<T: WithSane> WithSane for std::sync::Mutex<T> {
     type Auth = T::Auth;
 
     fn with_sane<R>(&self, cb: impl for<'a> FnOnce(&'a Sane<Self::Auth>) -> R) -> R {
-        self.lock().expect("poisoned Mutex").with_sane(cb)
+        self.lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .with_sane(cb)
     }
 }
 
@@ -166,6 +220,27 @@ impl<T: WithSane> WithSane for std::sync::MutexGuard<'_, T> {
     }
 }
 
+/// SANE requires exclusive access, therefore the write lock is taken.
+/// A read lock is insufficient, as the SANE API is not reentrant.
+/// A poisoned RwLock is recovered, see the implementation for [`std::sync::Mutex`].
+impl<T: WithSane> WithSane for std::sync::RwLock<T> {
+    type Auth = T::Auth;
+
+    fn with_sane<R>(&self, cb: impl for<'a> FnOnce(&'a Sane<Self::Auth>) -> R) -> R {
+        self.write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .with_sane(cb)
+    }
+}
+
+impl<T: WithSane> WithSane for std::sync::RwLockWriteGuard<'_, T> {
+    type Auth = T::Auth;
+
+    fn with_sane<R>(&self, cb: impl for<'a> FnOnce(&'a Sane<Self::Auth>) -> R) -> R {
+        (**self).with_sane(cb)
+    }
+}
+
 impl<T: WithSane> WithSane for std::sync::Arc<T> {
     type Auth = T::Auth;
 
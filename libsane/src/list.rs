@@ -19,6 +19,19 @@ pub(crate) unsafe fn new_word_list<'a, T>(data: *const sys::Int) -> &'a [T] {
     std::slice::from_raw_parts(data, len)
 }
 
+/// Reads the length of a word list like [`new_word_list`], but returns `None` if `data`
+/// is null or misaligned, or if the length is negative.
+///
+/// # Safety
+/// If `data` is non-null and aligned, it must point to a valid `sys::Int`.
+pub(crate) unsafe fn word_list_len(data: *const sys::Int) -> Option<usize> {
+    if data.is_null() || !data.is_aligned() {
+        return None;
+    }
+    // SAFETY: data is a valid `sys::Int` representing the size
+    usize::try_from(*data).ok()
+}
+
 #[repr(transparent)]
 pub struct SaneStrListIter<'a> {
     data: *const sys::StringConst,
@@ -59,8 +72,10 @@ impl<'a> SaneStrListIter<'a> {
 
 impl Default for SaneStrListIter<'_> {
     fn default() -> Self {
+        /// An empty list only consisting of the NULL terminator.
+        const EMPTY: &[sys::StringConst] = &[std::ptr::null()];
         Self {
-            data: std::ptr::null(),
+            data: EMPTY.as_ptr(),
             _phant: PhantomData,
         }
     }
@@ -87,4 +102,11 @@ impl<'a> Iterator for SaneStrListIter<'a> {
             Some(unsafe { SaneStr::from_ptr(item) })
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.count_items();
+        (len, Some(len))
+    }
 }
+
+impl ExactSizeIterator for SaneStrListIter<'_> {}
@@ -1,9 +1,7 @@
-use std::{
-    ffi::c_void,
-    mem::MaybeUninit,
-    os::fd::{FromRawFd, OwnedFd},
-    ptr::NonNull,
-};
+use std::{ffi::c_void, mem::MaybeUninit, ptr::NonNull};
+
+#[cfg(unix)]
+use std::os::fd::RawFd;
 
 use crate::{error, sys, sys_bool, ControlInfo, Error, Sane, SaneStr};
 
@@ -115,15 +113,16 @@ impl<A> Sane<A> {
         &self,
         handle: NonNull<c_void>,
         index: u32,
-    ) -> Result<(), Error> {
+    ) -> Result<ControlInfo, Error> {
+        let mut info: sys::Int = 0;
         error::status_result(sys::sane_control_option(
             handle.as_ptr(),
             index.try_into().expect("invalid index"),
             sys::Action::SetAuto,
             std::ptr::null_mut(),
-            std::ptr::null_mut(),
+            &mut info,
         ))?;
-        Ok(())
+        Ok(ControlInfo::from_bits_retain(info as u32))
     }
 
     /// This function is used to obtain the current scan parameters. The returned parameters
@@ -290,19 +289,31 @@ impl<A> Sane<A> {
     /// # Safety
     /// The device must be scanning, i.e. this function must be called after [`Self::sys_start`] and before [`Self::sys_read`]
     /// fails with status [`Eof`][`crate::error::Status::Eof`] or the image acquisition is cancelled.
-    /// The file descriptor must be closed afterwards.
+    /// The file descriptor is owned by the backend and must not be closed.
     ///
     /// # Errors
     /// - [`Inval`][`crate::error::Status::Inval`]: No image acquisition is pending.
     /// - [`Unsupported`][`crate::error::Status::Unsupported`]: The backend does not support
     ///   the requested I/O mode.
-    pub(crate) unsafe fn sys_get_select_fd(
-        &self,
-        handle: NonNull<c_void>,
-    ) -> Result<OwnedFd, Error> {
+    #[cfg(unix)]
+    pub(crate) unsafe fn sys_get_select_fd(&self, handle: NonNull<c_void>) -> Result<RawFd, Error> {
         let mut fd = 0;
         error::status_result(sys::sane_get_select_fd(handle.as_ptr(), &mut fd))?;
-        Ok(OwnedFd::from_raw_fd(fd))
+        Ok(fd)
+    }
+
+    /// Select file-descriptors are only supported on Unix, this always fails with
+    /// [`Unsupported`][`crate::error::Status::Unsupported`].
+    ///
+    /// # Safety
+    /// This function is always safe to call, it is only unsafe for consistency with the
+    /// Unix implementation.
+    #[cfg(not(unix))]
+    pub(crate) unsafe fn sys_get_select_fd(
+        &self,
+        _handle: NonNull<c_void>,
+    ) -> Result<std::convert::Infallible, Error> {
+        Err(Error::from_sys_status(sys::Status::Unsupported))
     }
 }
 
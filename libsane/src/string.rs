@@ -2,12 +2,13 @@ use core::fmt;
 use std::{
     borrow::Borrow,
     cmp::Ordering,
-    ffi::{c_char, CStr},
+    ffi::{c_char, CStr, CString, NulError, OsString},
     fmt::{Debug, Display, Write},
     hash::Hash,
     iter::FusedIterator,
     marker::PhantomData,
     mem::MaybeUninit,
+    str::FromStr,
 };
 
 use crate::slice_util::{assume_init_slice, new_uninit_boxed_slice, slice_as_maybe_uninit};
@@ -44,6 +45,22 @@ impl SaneStr {
         self.0.count_bytes() + 1
     }
 
+    /// Length of this string in bytes, excluding the NUL terminator.
+    /// Same as [`Self::count_bytes`].
+    pub fn len(&self) -> usize {
+        self.count_bytes()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Amount of characters in this string. As SANE strings are Latin-1 encoded,
+    /// this is the same as the length in bytes.
+    pub fn char_count(&self) -> usize {
+        self.count_bytes()
+    }
+
     pub fn to_bytes(&self) -> &[u8] {
         self.0.to_bytes()
     }
@@ -56,15 +73,62 @@ impl SaneStr {
         self.0.as_ptr()
     }
 
+    /// Checks that two strings are equal, ignoring the case of ASCII characters.
+    /// Other Latin-1 characters are compared exactly.
+    pub fn eq_ignore_ascii_case(&self, other: &SaneStr) -> bool {
+        self.to_bytes().eq_ignore_ascii_case(other.to_bytes())
+    }
+
+    pub fn starts_with(&self, prefix: &SaneStr) -> bool {
+        self.to_bytes().starts_with(prefix.to_bytes())
+    }
+
     pub fn chars(&self) -> Chars {
         // SAFETY: self is a valid C-String
         unsafe { Chars::new(self.as_ptr()) }
     }
 
+    /// Decodes this string as UTF-8, replacing invalid sequences with
+    /// [`char::REPLACEMENT_CHARACTER`].
+    ///
+    /// The SANE standard specifies strings to be encoded in Latin-1, which is what
+    /// [`Self::chars`] decodes. However, some backends emit UTF-8 encoded strings instead.
+    /// As both encodings cannot be told apart reliably, the caller has to choose.
+    pub fn chars_utf8(&self) -> impl Iterator<Item = char> + '_ {
+        self.to_bytes().utf8_chunks().flat_map(|chunk| {
+            let invalid = (!chunk.invalid().is_empty()).then_some(char::REPLACEMENT_CHARACTER);
+            chunk.valid().chars().chain(invalid)
+        })
+    }
+
     pub fn bytes(&self) -> Bytes {
         // SAFETY: self is a valid C-String
         unsafe { Bytes::new(self.as_ptr()) }
     }
+
+    /// Copies this string into an owned [`CString`], for example to pass it to an FFI
+    /// function that takes ownership of it.
+    pub fn to_c_string(&self) -> CString {
+        self.0.to_owned()
+    }
+
+    /// Converts this string into an [`OsString`], for example to pass a device name as an
+    /// argument to [`std::process::Command`].
+    ///
+    /// On Unix, the bytes are passed through unchanged. On other platforms, the string is
+    /// decoded as Latin-1 (see [`Self::chars`]), so the bytes received by other programs
+    /// may differ from the original ones.
+    pub fn to_os_string(&self) -> OsString {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStringExt;
+            OsString::from_vec(self.to_bytes().to_vec())
+        }
+        #[cfg(not(unix))]
+        {
+            OsString::from(self.chars().collect::<String>())
+        }
+    }
 }
 
 impl AsRef<CStr> for SaneStr {
@@ -104,12 +168,17 @@ pub struct SaneString(
 );
 
 impl SaneString {
+    /// Creates an empty string that fits `reserve` bytes including the NUL terminator.
+    ///
+    /// # Panics
+    /// If `reserve` is zero.
     pub fn with_capacity(reserve: usize) -> Self {
         assert_ne!(
             reserve, 0,
             "SaneString must be at least one byte in size to fit a NUL"
         );
-        let buf = new_uninit_boxed_slice(reserve);
+        let mut buf = new_uninit_boxed_slice(reserve);
+        buf[0] = MaybeUninit::new(0);
         Self(buf)
     }
 
@@ -120,10 +189,68 @@ impl SaneString {
         buf
     }
 
+    /// Creates a string from Latin-1 encoded bytes, for example read from a configuration
+    /// file. A NUL terminator is appended unless `bytes` already ends with one.
+    ///
+    /// # Errors
+    /// If `bytes` contains a NUL character before its end, which would truncate the string.
+    pub fn from_bytes(mut bytes: Vec<u8>) -> Result<Self, NulError> {
+        if bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        let c = CString::new(bytes)?;
+        Ok(Self::from_cstr(&c))
+    }
+
+    /// Replaces the contents of this string, keeping the capacity.
+    ///
+    /// # Panics
+    /// If the value including its NUL terminator does not fit into the capacity. See
+    /// [`Self::try_set_contents`] for a fallible version.
     pub fn set_contents(&mut self, value: &SaneStr) {
+        if let Err(err) = self.try_set_contents(value) {
+            panic!("{err}");
+        }
+    }
+
+    /// Replaces the contents of this string, keeping the capacity. Fails if the value
+    /// including its NUL terminator does not fit into the capacity.
+    pub fn try_set_contents(&mut self, value: &SaneStr) -> Result<(), CapacityError> {
         let bytes = value.to_bytes_with_nul();
-        assert!(bytes.len() <= self.capacity());
+        if bytes.len() > self.capacity() {
+            return Err(CapacityError {
+                required: bytes.len(),
+                capacity: self.capacity(),
+            });
+        }
         self.0[..bytes.len()].copy_from_slice(slice_as_maybe_uninit(bytes));
+        Ok(())
+    }
+
+    /// Truncates this string to a length of zero, keeping the capacity.
+    pub fn clear(&mut self) {
+        self.0[0] = MaybeUninit::new(0);
+    }
+
+    /// Shortens this string to `len` bytes, keeping the capacity.
+    /// Has no effect if `len` is greater or equal to the string's current length.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.count_bytes() {
+            self.0[len] = MaybeUninit::new(0);
+        }
+    }
+
+    /// Ensures that the capacity is large enough to fit at least `additional`
+    /// more bytes in addition to the current contents and NUL terminator.
+    pub fn reserve(&mut self, additional: usize) {
+        let len = self.count_bytes_with_nul();
+        let required = len.checked_add(additional).expect("capacity overflow");
+        if required <= self.capacity() {
+            return;
+        }
+        let mut buf = new_uninit_boxed_slice(required);
+        buf[..len].copy_from_slice(&self.0[..len]);
+        self.0 = buf;
     }
 
     pub fn count_bytes(&self) -> usize {
@@ -150,6 +277,23 @@ impl SaneString {
         }
     }
 
+    /// Length of this string in bytes, excluding the NUL terminator.
+    /// Same as [`Self::count_bytes`].
+    pub fn len(&self) -> usize {
+        self.count_bytes()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        // SAFETY: by invariants
+        unsafe { self.0[0].assume_init() == 0 }
+    }
+
+    /// Amount of characters in this string. As SANE strings are Latin-1 encoded,
+    /// this is the same as the length in bytes.
+    pub fn char_count(&self) -> usize {
+        self.count_bytes()
+    }
+
     pub fn to_bytes(&self) -> &[u8] {
         let len = self.count_bytes();
         // SAFETY: by invariants
@@ -179,12 +323,104 @@ impl SaneString {
         unsafe { Chars::new(self.as_ptr()) }
     }
 
+    /// See [`SaneStr::chars_utf8`].
+    pub fn chars_utf8(&self) -> impl Iterator<Item = char> + '_ {
+        <Self as Borrow<SaneStr>>::borrow(self).chars_utf8()
+    }
+
     pub fn bytes(&self) -> Bytes {
         // SAFETY: self is a valid C-String (by invariants)
         unsafe { Bytes::new(self.as_ptr()) }
     }
 }
 
+/// Error returned when a string does not fit into the capacity of a [`SaneString`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError {
+    /// Number of bytes required, including the NUL terminator.
+    pub required: usize,
+    /// Capacity of the string in bytes.
+    pub capacity: usize,
+}
+
+impl Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "string of {} bytes does not fit into a capacity of {} bytes",
+            self.required, self.capacity
+        )
+    }
+}
+
+impl std::error::Error for CapacityError {}
+
+/// Error returned when converting a [`str`] into a [`SaneString`] fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromStrError {
+    /// The string contains characters outside of Latin-1.
+    NotLatin1,
+    /// The string contains a NUL character, which would truncate it.
+    InteriorNul,
+}
+
+impl Display for FromStrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NotLatin1 => "string contains non-Latin1 characters",
+            Self::InteriorNul => "string contains a NUL character",
+        })
+    }
+}
+
+impl std::error::Error for FromStrError {}
+
+impl TryFrom<&str> for SaneString {
+    type Error = FromStrError;
+
+    /// Encodes the string as Latin-1.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let bytes = value
+            .chars()
+            .map(u8::try_from)
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| FromStrError::NotLatin1)?;
+        let c = CString::new(bytes).map_err(|_| FromStrError::InteriorNul)?;
+        Ok(Self::from_cstr(&c))
+    }
+}
+
+impl FromStr for SaneString {
+    type Err = FromStrError;
+
+    /// See [`SaneString::try_from`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+/// Appends Latin-1 text, growing the capacity as needed. Fails if the text contains
+/// characters outside of Latin-1 or NUL.
+impl Write for SaneString {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let start = self.count_bytes();
+        let count = s.chars().count();
+        self.reserve(count);
+        for (i, ch) in s.chars().enumerate() {
+            match u8::try_from(ch) {
+                Ok(latin1) if latin1 != 0 => self.0[start + i] = MaybeUninit::new(latin1),
+                _ => {
+                    // restore the previous contents
+                    self.0[start] = MaybeUninit::new(0);
+                    return Err(fmt::Error);
+                }
+            }
+        }
+        self.0[start + count] = MaybeUninit::new(0);
+        Ok(())
+    }
+}
+
 impl AsRef<CStr> for SaneString {
     fn as_ref(&self) -> &CStr {
         // SAFETY: self is a valid C-String (by invariants)
@@ -216,7 +452,7 @@ impl Borrow<SaneStr> for SaneString {
 
 impl PartialEq for SaneString {
     fn eq(&self, other: &Self) -> bool {
-        self.chars().zip(other.chars()).all(|(a, b)| a == b)
+        self.to_bytes() == other.to_bytes()
     }
 }
 
@@ -230,25 +466,15 @@ impl PartialOrd for SaneString {
 
 impl Ord for SaneString {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.chars()
-            .zip(other.chars())
-            .find_map(|(a, b)| {
-                let ord = a.cmp(&b);
-                if ord.is_eq() {
-                    None
-                } else {
-                    Some(ord)
-                }
-            })
-            .unwrap_or(Ordering::Equal)
+        // Must be consistent with the implementation for SaneStr, see Borrow.
+        self.to_bytes().cmp(other.to_bytes())
     }
 }
 
 impl Hash for SaneString {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        for ch in self.bytes() {
-            state.write_i8(ch)
-        }
+        // Must be consistent with the implementation for SaneStr, see Borrow.
+        Hash::hash(Borrow::<SaneStr>::borrow(self), state)
     }
 }
 
@@ -315,8 +541,9 @@ impl Iterator for Chars<'_> {
         } else {
             // SAFETY: NUL terminator was not hit => next byte is valid as well
             self.data = unsafe { self.data.add(1) };
-            // SAFETY: Latin-1 is a subset of UTF-8
-            Some(unsafe { char::from_u32_unchecked(ch as u32) })
+            // Latin-1 is a subset of Unicode. `c_char` may be signed, so it is converted
+            // to `u8` first.
+            Some(char::from(ch as u8))
         }
     }
 }
@@ -365,3 +592,146 @@ impl Debug for SaneString {
         f.write_char('"')
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SaneString {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SaneString {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let s = <std::borrow::Cow<str>>::deserialize(deserializer)?;
+        Self::try_from(&*s).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashSet};
+
+    use super::*;
+
+    fn sane(s: &str) -> SaneString {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn eq_and_ord_compare_bytes() {
+        assert_eq!(sane("abc"), sane("abc"));
+        assert_ne!(sane("a"), sane("ab"));
+        assert_ne!(sane(""), sane("a"));
+        assert!(sane("a") < sane("ab"));
+        assert!(sane("ab") < sane("b"));
+        assert_eq!(sane("ab").cmp(&sane("a")), Ordering::Greater);
+        // Latin-1 bytes above ASCII sort after ASCII, regardless of the sign of c_char
+        assert!(sane("z") < sane("\u{e9}"));
+    }
+
+    #[test]
+    fn consistent_with_borrowed() {
+        let strings = ["", "a", "ab", "b", "\u{e9}"].map(sane);
+        for a in &strings {
+            for b in &strings {
+                let (a_str, b_str): (&SaneStr, &SaneStr) = (a.borrow(), b.borrow());
+                assert_eq!(a == b, a_str == b_str);
+                assert_eq!(a.cmp(b), a_str.cmp(b_str));
+            }
+        }
+        let tree: BTreeSet<SaneString> = strings.iter().cloned().collect();
+        let hash: HashSet<SaneString> = strings.iter().cloned().collect();
+        for s in &strings {
+            let s: &SaneStr = s.borrow();
+            assert!(tree.contains(s));
+            assert!(hash.contains(s));
+        }
+        let missing: &SaneStr = SaneStr::from_cstr(c"abc");
+        assert!(!tree.contains(missing));
+        assert!(!hash.contains(missing));
+    }
+
+    #[test]
+    fn reserve_grows_capacity() {
+        let mut s = SaneString::with_capacity(1);
+        assert_eq!(s.capacity(), 1);
+        assert!(s.is_empty());
+        s.reserve(0);
+        assert_eq!(s.capacity(), 1);
+        s.reserve(3);
+        assert_eq!(s.capacity(), 4);
+        assert!(s.is_empty());
+
+        let mut s = sane("abc");
+        let capacity = s.capacity();
+        s.reserve(0);
+        assert_eq!(s.capacity(), capacity);
+        s.reserve(5);
+        assert!(s.capacity() >= 9);
+        assert_eq!(s.to_bytes(), b"abc");
+    }
+
+    #[test]
+    fn write_grows_capacity() {
+        let mut s = SaneString::with_capacity(1);
+        write!(s, "abc").unwrap();
+        assert_eq!(s.to_bytes(), b"abc");
+        write!(s, "{}", "d".repeat(100)).unwrap();
+        assert_eq!(s.count_bytes(), 103);
+        assert!(s.capacity() >= 104);
+        write!(s, "\u{e9}").unwrap();
+        assert_eq!(s.to_bytes().last(), Some(&0xE9));
+    }
+
+    #[test]
+    fn write_restores_on_error() {
+        let mut s = sane("abc");
+        assert!(write!(s, "de\u{20ac}f").is_err());
+        assert_eq!(s.to_bytes(), b"abc");
+        assert!(write!(s, "x\0y").is_err());
+        assert_eq!(s.to_bytes(), b"abc");
+        write!(s, "d").unwrap();
+        assert_eq!(s.to_bytes(), b"abcd");
+    }
+
+    #[test]
+    fn chars_utf8_decodes_multi_byte_sequences() {
+        // "Größe €" encoded as UTF-8
+        let s = SaneString::from_bytes(b"Gr\xC3\xB6\xC3\x9Fe \xE2\x82\xAC".to_vec()).unwrap();
+        assert_eq!(s.chars_utf8().collect::<String>(), "Größe €");
+        // the same bytes decoded as Latin-1
+        assert_eq!(s.chars().count(), s.count_bytes());
+        // a truncated sequence is replaced
+        let s = SaneString::from_bytes(b"a\xE2\x82b".to_vec()).unwrap();
+        assert_eq!(s.chars_utf8().collect::<String>(), "a\u{FFFD}b");
+    }
+
+    #[test]
+    fn chars_decodes_latin1_above_ascii() {
+        let s = SaneString::from_bytes(vec![0xE9]).unwrap();
+        assert_eq!(s.chars().collect::<Vec<_>>(), ['\u{e9}']);
+        assert_eq!(s.to_string(), "\u{e9}");
+        let all: SaneString = (1..=u8::MAX)
+            .map(char::from)
+            .collect::<String>()
+            .parse()
+            .unwrap();
+        assert!(all.chars().map(u32::from).eq(1..=u8::MAX.into()));
+    }
+
+    #[test]
+    fn from_str_rejects_invalid() {
+        assert_eq!("\u{e9}".parse::<SaneString>().unwrap().to_bytes(), [0xE9]);
+        assert_eq!(
+            "\u{20ac}".parse::<SaneString>().unwrap_err(),
+            FromStrError::NotLatin1
+        );
+        assert_eq!(
+            SaneString::try_from("a\0b").unwrap_err(),
+            FromStrError::InteriorNul
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::borrow::Borrow;
+use std::{borrow::Borrow, error::Error as StdError, fmt};
 
 use crate::{fixed::Fixed, sys, sys_bool, SaneStr, SaneString};
 
@@ -21,6 +21,32 @@ impl ValueType {
     pub const fn is_value(&self) -> bool {
         matches!(self, Self::Bool | Self::Int | Self::Fixed | Self::String)
     }
+
+    pub const fn is_string(&self) -> bool {
+        matches!(self, Self::String)
+    }
+
+    pub const fn is_button(&self) -> bool {
+        matches!(self, Self::Button)
+    }
+
+    pub const fn is_group(&self) -> bool {
+        matches!(self, Self::Group)
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Bool => "boolean",
+            Self::Int => "integer",
+            Self::Fixed => "fixed",
+            Self::String => "string",
+            Self::Group => "group",
+            Self::Button => "button",
+            Self::Unknown => "unknown",
+        })
+    }
 }
 
 impl From<sys::ValueType> for ValueType {
@@ -37,6 +63,50 @@ impl From<sys::ValueType> for ValueType {
     }
 }
 
+/// Physical unit of an option value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+    /// The value is unit-less, such as a page count.
+    None,
+    Pixel,
+    Bit,
+    Millimeter,
+    Dpi,
+    Percent,
+    Microsecond,
+    Unknown,
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::None => "",
+            Self::Pixel => "px",
+            Self::Bit => "bit",
+            Self::Millimeter => "mm",
+            Self::Dpi => "dpi",
+            Self::Percent => "%",
+            Self::Microsecond => "µs",
+            Self::Unknown => "?",
+        })
+    }
+}
+
+impl From<sys::Unit> for Unit {
+    fn from(value: sys::Unit) -> Self {
+        match value {
+            sys::Unit::None => Self::None,
+            sys::Unit::Pixel => Self::Pixel,
+            sys::Unit::Bit => Self::Bit,
+            sys::Unit::Mm => Self::Millimeter,
+            sys::Unit::Dpi => Self::Dpi,
+            sys::Unit::Percent => Self::Percent,
+            sys::Unit::Microsecond => Self::Microsecond,
+            _ => Self::Unknown,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Value<'a> {
     Bool(bool),
@@ -75,6 +145,7 @@ impl Value<'_> {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OwnedValue {
     Bool(bool),
     Int(i32),
@@ -119,3 +190,57 @@ impl OwnedValue {
         }
     }
 }
+
+impl From<Value<'_>> for OwnedValue {
+    fn from(value: Value<'_>) -> Self {
+        match value {
+            Value::Bool(v) => Self::Bool(v),
+            Value::Int(v) => Self::Int(v),
+            Value::Fixed(v) => Self::Fixed(v),
+            Value::String(v) => Self::String(v.to_owned()),
+        }
+    }
+}
+
+/// Error returned when converting a [`Value`] or [`OwnedValue`] into a concrete type fails
+/// because the value is of another type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WrongValueType {
+    pub expected: ValueType,
+    pub got: ValueType,
+}
+
+impl fmt::Display for WrongValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {}, got {}", self.expected, self.got)
+    }
+}
+
+impl StdError for WrongValueType {}
+
+macro_rules! impl_try_from_value {
+    ($value:ident $(<$lt:lifetime>)?, $variant:ident => $target:ty) => {
+        impl$(<$lt>)? TryFrom<$value$(<$lt>)?> for $target {
+            type Error = WrongValueType;
+
+            fn try_from(value: $value$(<$lt>)?) -> Result<Self, Self::Error> {
+                match value {
+                    $value::$variant(v) => Ok(v),
+                    other => Err(WrongValueType {
+                        expected: ValueType::$variant,
+                        got: other.type_of(),
+                    }),
+                }
+            }
+        }
+    };
+}
+
+impl_try_from_value!(Value<'a>, Bool => bool);
+impl_try_from_value!(Value<'a>, Int => i32);
+impl_try_from_value!(Value<'a>, Fixed => Fixed);
+impl_try_from_value!(Value<'a>, String => &'a SaneStr);
+impl_try_from_value!(OwnedValue, Bool => bool);
+impl_try_from_value!(OwnedValue, Int => i32);
+impl_try_from_value!(OwnedValue, Fixed => Fixed);
+impl_try_from_value!(OwnedValue, String => SaneString);